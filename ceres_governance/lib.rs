@@ -1,38 +1,450 @@
 #![cfg_attr(not(feature = "std"), no_std, no_main)]
 
+/// Interface a staking contract must expose to boost the voting power of stakers
+#[ink::trait_definition]
+pub trait Staking {
+    /// Amount currently staked by the owner
+    #[ink(message)]
+    fn staked_of(&self, owner: ink::primitives::AccountId) -> u128;
+}
+
 #[ink::contract]
 mod ceres_governance {
 
-    use ink::storage::Mapping;
+    use ink::storage::{Lazy, Mapping};
     use scale::{Decode, Encode};
     use ink::prelude::string::String;
+    use ink::prelude::vec::Vec;
+    #[cfg(not(test))]
+    use ink::env::call::{build_call, ExecutionInput, Selector};
+    #[cfg(not(test))]
+    use ink::env::DefaultEnvironment;
+    use ink::env::hash::Blake2x256;
 
-    #[derive(Encode, Decode, Default, PartialEq, Eq)]
+    /// Contract version as (major, minor, patch)
+    pub const CONTRACT_VERSION: (u16, u16, u16) = (4, 3, 0);
+    /// Default maximum poll duration, 365 days in milliseconds
+    pub const DEFAULT_MAX_POLL_DURATION: Timestamp = 365 * 24 * 60 * 60 * 1000;
+    /// Default minimum poll duration, 1 hour in milliseconds
+    pub const DEFAULT_MIN_POLL_DURATION: Timestamp = 60 * 60 * 1000;
+    /// Expected block time in milliseconds, converts the poll duration bounds for block number windows
+    pub const EXPECTED_BLOCK_TIME: Timestamp = 6_000;
+    /// Default maximum number of options per poll
+    pub const DEFAULT_MAX_OPTIONS: u32 = 256;
+    /// Maximum number of entries returned by a paginated query
+    pub const MAX_PAGE_SIZE: u32 = 100;
+    /// Maximum length of a poll id in bytes
+    pub const MAX_POLL_ID_LENGTH: usize = 64;
+    /// Maximum length of an option label in bytes
+    pub const MAX_OPTION_LABEL_LENGTH: usize = 64;
+    /// Maximum length of all option labels of a poll together in bytes, keeps them inside the storage buffer
+    pub const MAX_OPTION_LABELS_LENGTH: usize = 8192;
+    /// Maximum length of a poll title in bytes
+    pub const MAX_TITLE_LENGTH: usize = 128;
+    /// Maximum length of a poll description in bytes
+    pub const MAX_DESCRIPTION_LENGTH: usize = 2048;
+    /// Maximum length of a poll category in bytes
+    pub const MAX_CATEGORY_LENGTH: usize = 32;
+    /// Maximum length of a delegation chain followed when checking for cycles
+    pub const MAX_DELEGATION_DEPTH: u32 = 16;
+    /// Maximum number of entries accepted by a batch message
+    pub const MAX_BATCH_SIZE: usize = 50;
+    /// Denominator of the staking boost multiplier, a multiplier of BOOST_DENOMINATOR is 1x
+    pub const BOOST_DENOMINATOR: u32 = 10_000;
+    /// Denominator of the vote fee, fees are expressed in basis points of the votes
+    pub const FEE_DENOMINATOR: u16 = 10_000;
+    /// Maximum cancel penalty in basis points, a cancelled poll always returns most of the escrow
+    pub const MAX_CANCEL_PENALTY_BPS: u16 = 1_000;
+
+    #[derive(Encode, Decode, Default, PartialEq, Eq, Debug)]
     #[cfg_attr(feature = "std",derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
     pub struct VotingInfo {
-        /// Voting option
-        voting_option: u32,
+        /// Voting option, valid options are 1..=number_of_options, 0 is abstain on polls that allow it and unused for a MultiChoice poll
+        pub voting_option: u32,
         /// Number of votes
-        number_of_votes: Balance,
+        pub number_of_votes: Balance,
         /// Ceres withdrawn
-        ceres_withdrawn: bool,
+        pub ceres_withdrawn: bool,
+        /// Ceres locked in the contract for the votes
+        pub locked_amount: Balance,
+        /// Voter has voted on the poll
+        pub has_voted: bool,
+        /// Votes counted in the tally, after any staking boost or time decay
+        pub tally_votes: Balance,
+    }
+
+    #[derive(Encode, Decode, Default, PartialEq, Eq, Debug, Clone, Copy)]
+    #[cfg_attr(feature = "std",derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub enum VotingMode {
+        /// Each vote costs one CERES
+        #[default]
+        Linear,
+        /// Casting n votes costs n^2 CERES
+        Quadratic,
+        /// Votes can be split across several options with vote_weighted
+        MultiChoice,
+        /// Votes cost one CERES each and count less the later they are cast in the poll window
+        TimeDecay,
+        /// Every account casts exactly one vote and no CERES is locked
+        OnePerAccount,
+    }
+
+    #[derive(Encode, Decode, Default, PartialEq, Eq, Debug, Clone, Copy)]
+    #[cfg_attr(feature = "std",derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub enum PollWindow {
+        /// Poll start and end are block timestamps in milliseconds
+        #[default]
+        Timestamp,
+        /// Poll start and end are block numbers
+        BlockNumber,
+    }
+
+    #[derive(Encode, Decode, Default, PartialEq, Eq, Debug, Clone, Copy)]
+    #[cfg_attr(feature = "std",derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub enum Rounding {
+        /// The fractional part of the quadratic cost is dropped
+        #[default]
+        Floor,
+        /// Any fractional part of the quadratic cost is charged as one more base unit
+        Ceil,
+    }
+
+    #[derive(Encode, Decode, Default, PartialEq, Eq, Debug, Clone, Copy)]
+    #[cfg_attr(feature = "std",derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub enum TieBreak {
+        /// The lowest tied option wins
+        #[default]
+        LowestIndex,
+        /// A tie has no winner
+        NoWinner,
+        /// A tie has to be resolved by extending the poll
+        RequireExtension,
     }
 
     #[derive(Encode, Decode, Default, PartialEq, Eq, Debug)]
     #[cfg_attr(feature = "std",derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
     pub struct PollInfo {
-        /// Number of options
+        /// Number of options, numbered 1..=number_of_options
         pub number_of_options: u32,
         /// Poll start timestamp
         pub poll_start_timestamp: Timestamp,
         /// Poll end timestamp
         pub poll_end_timestamp: Timestamp,
+        /// Poll closed
+        pub closed: bool,
+        /// Poll title
+        pub title: String,
+        /// Poll description
+        pub description: String,
+        /// Poll cancelled
+        pub cancelled: bool,
+        /// Block at which voting power is measured
+        pub snapshot_block: BlockNumber,
+        /// Settings the poll was created with, activate_poll clears draft, lower_quorum lowers quorum and extend_poll moves the reveal end
+        pub settings: PollSettings,
+    }
+
+    #[derive(Encode, Decode, PartialEq, Eq, Debug, Clone, Copy)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub enum PollStatus {
+        /// Poll is a draft awaiting activation
+        Draft,
+        /// Voting has not started yet
+        NotStarted,
+        /// Voting is open
+        Active,
+        /// Voting has ended
+        Finished,
+        /// Poll was cancelled by the owner
+        Cancelled,
+        /// Poll has ended and its result was finalized with close_poll
+        Closed,
+    }
+
+    #[derive(Encode, Decode, PartialEq, Eq, Debug, Clone, Copy)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum WithdrawOutcome {
+        /// Poll reached quorum, with the refunded amount
+        QuorumReached(Balance),
+        /// Poll failed to reach quorum, with the refunded amount
+        QuorumFailed(Balance),
+    }
+
+    #[derive(Encode, Decode, PartialEq, Eq, Debug, Clone)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct PollSettings {
+        /// Minimum total votes for the poll to be valid, 0 means no quorum
+        pub quorum: Balance,
+        /// Maximum votes a single voter can cast, 0 means unlimited
+        pub max_votes_per_voter: Balance,
+        /// How the CERES cost of votes is computed
+        pub voting_mode: VotingMode,
+        /// Minimum votes a single vote call must carry, counted before the protocol fee is taken
+        pub min_votes: Balance,
+        /// Token locked by the poll, None means the contract's default token
+        pub token: Option<AccountId>,
+        /// Only allowed voters can vote
+        pub restricted: bool,
+        /// End of the reveal window for commit-reveal voting, 0 means votes are cast directly
+        pub reveal_end_timestamp: Timestamp,
+        /// Unit of the poll start, end and reveal end
+        pub window: PollWindow,
+        /// Delay after the poll end before funds unlock, in the unit of the poll window
+        pub withdrawal_delay: Timestamp,
+        /// Total votes the tally can reach, votes that would take it further are rejected, 0 means no cap
+        pub max_total_votes: Balance,
+        /// Option 0 is an abstain vote that counts toward quorum but can never win
+        pub allow_abstain: bool,
+        /// Time after the poll start during which votes are still refused, in the units of the poll window
+        pub start_buffer: Timestamp,
+        /// Token base units in one whole vote of a Quadratic poll, 10^decimals makes n whole votes cost n^2 whole tokens
+        pub vote_unit: Balance,
+        /// Rounding of the quadratic cost when votes are not a whole number of vote units
+        pub rounding: Rounding,
+        /// Poll is announced for review and rejects votes until activate_poll is called
+        pub draft: bool,
+        /// How a tie between the leading options is resolved
+        pub tie_break: TieBreak,
+        /// Distinct voters needed on top of the token quorum, 0 means no minimum
+        pub min_unique_voters: u32,
+        /// Votes must be a multiple of this step before the protocol fee is taken, 0 allows any amount
+        pub vote_step: Balance,
+        /// Category the poll is listed under, empty for none
+        pub category: String,
+    }
+
+    impl Default for PollSettings {
+        fn default() -> Self {
+            Self {
+                quorum: 0,
+                max_votes_per_voter: 0,
+                voting_mode: VotingMode::Linear,
+                min_votes: 1,
+                token: None,
+                restricted: false,
+                reveal_end_timestamp: 0,
+                window: PollWindow::Timestamp,
+                withdrawal_delay: 0,
+                max_total_votes: 0,
+                allow_abstain: false,
+                start_buffer: 0,
+                vote_unit: 1,
+                rounding: Rounding::Floor,
+                draft: false,
+                tie_break: TieBreak::LowestIndex,
+                min_unique_voters: 0,
+                vote_step: 0,
+                category: String::new(),
+            }
+        }
+    }
+
+    /// Owner-gated action executed once enough admins confirm it
+    #[derive(Encode, Decode, PartialEq, Eq, Debug, Clone)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    #[allow(clippy::large_enum_variant)]
+    pub enum AdminAction {
+        /// Run create_poll
+        CreatePoll {
+            poll_id: String,
+            number_of_options: u32,
+            poll_start_timestamp: Timestamp,
+            poll_end_timestamp: Timestamp,
+            settings: PollSettings,
+            option_labels: Vec<String>,
+            title: String,
+            description: String,
+        },
+        /// Run activate_poll
+        ActivatePoll {
+            poll_id: String,
+        },
+        /// Run extend_poll
+        ExtendPoll {
+            poll_id: String,
+            new_end_timestamp: Timestamp,
+        },
+        /// Run lower_quorum
+        LowerQuorum {
+            poll_id: String,
+            new_quorum: Balance,
+        },
+        /// Run cancel_poll
+        CancelPoll {
+            poll_id: String,
+        },
+        /// Run emergency_refund_all
+        EmergencyRefundAll {
+            poll_id: String,
+            voters: Vec<AccountId>,
+        },
+        /// Run update_poll_metadata
+        UpdatePollMetadata {
+            poll_id: String,
+            title: String,
+            description: String,
+        },
+        /// Run set_voter_allowed
+        SetVoterAllowed {
+            poll_id: String,
+            voter: AccountId,
+            allowed: bool,
+        },
+        /// Run set_admins
+        SetAdmins {
+            admins: Vec<AccountId>,
+            threshold: u8,
+        },
+        /// Run set_paused
+        SetPaused(bool),
+        /// Run sweep_excess
+        SweepExcess {
+            token: AccountId,
+            to: AccountId,
+        },
+        /// Run set_max_poll_duration
+        SetMaxPollDuration(Timestamp),
+        /// Run set_min_poll_duration
+        SetMinPollDuration(Timestamp),
+        /// Run set_max_options
+        SetMaxOptions(u32),
+        /// Run set_min_balance_to_create
+        SetMinBalanceToCreate(Balance),
+        /// Run set_staking_contract
+        SetStakingContract(Option<AccountId>),
+        /// Run set_boost_multiplier
+        SetBoostMultiplier(u32),
+        /// Run set_fee
+        SetFee(u16),
+        /// Run set_cancel_penalty
+        SetCancelPenalty(u16),
+        /// Run set_treasury
+        SetTreasury(AccountId),
+        /// Run set_code_hash
+        SetCodeHash(Hash),
+        /// Run create_poll_auto, the generated id is announced by PollCreated
+        CreatePollAuto {
+            number_of_options: u32,
+            poll_start_timestamp: Timestamp,
+            poll_end_timestamp: Timestamp,
+            settings: PollSettings,
+            option_labels: Vec<String>,
+            title: String,
+            description: String,
+        },
+    }
+
+    #[derive(Encode, Decode, PartialEq, Eq, Debug, Clone)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct AdminProposal {
+        /// Proposed action
+        pub action: AdminAction,
+        /// Admins that confirmed the action
+        pub confirmations: Vec<AccountId>,
+    }
+
+    #[derive(Encode, Decode, PartialEq, Eq, Debug, Clone)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct ContractInfo {
+        /// Contract admins
+        pub admins: Vec<AccountId>,
+        /// Confirmations required to execute an admin action
+        pub threshold: u8,
+        /// Default governance token
+        pub token: AccountId,
+        /// Voting is paused
+        pub paused: bool,
+        /// Number of created polls
+        pub poll_count: u32,
+    }
+
+    #[derive(Encode, Decode, PartialEq, Eq, Debug, Clone)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct GovernanceStats {
+        /// Number of created polls
+        pub poll_count: u32,
+        /// Active polls among the most recent MAX_PAGE_SIZE polls
+        pub active_polls: u32,
+        /// Votes cast across all polls, before any staking boost or time decay
+        pub votes_cast: Balance,
+        /// CERES currently locked in the contract
+        pub total_locked: Balance,
+    }
+
+    // State a validated vote would write
+    struct VoteUpdate {
+        poll_info: PollInfo,
+        voting_info: VotingInfo,
+        number_of_votes: Balance,
+        first_vote: bool,
+        amount: Balance,
+        fee: Balance,
+        boosted_votes: Balance,
+        tally_votes: Balance,
+        locked_balance: Balance,
+        total_locked: Balance,
+        option_votes: Balance,
+        voter_count: u32,
+    }
+
+    /// Errors returned by the PSP22 token contract
+    #[derive(Debug, PartialEq, Eq, Encode, Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum PSP22Error {
+        /// Custom error type for cases not covered by the standard
+        Custom(String),
+        /// Not enough balance
+        InsufficientBalance,
+        /// Not enough allowance
+        InsufficientAllowance,
+        /// Recipient is the zero address
+        ZeroRecipientAddress,
+        /// Sender is the zero address
+        ZeroSenderAddress,
+        /// Safe transfer check failed
+        SafeTransferCheckFailed(String),
     }
 
     #[ink(storage)]
     pub struct CeresGovernance {
+        admins: Vec<AccountId>,
+        threshold: u8,
+        admin_proposals: Mapping<u32, AdminProposal>,
+        next_proposal_id: u32,
+        // Set while a confirmed admin action runs, root storage is only written at the end of the message
+        executing: bool,
+        ceres_token: AccountId,
+        paused: bool,
+        max_poll_duration: Timestamp,
+        min_poll_duration: Timestamp,
+        max_options: u32,
+        min_balance_to_create: Balance,
         poll_data: Mapping<String, PollInfo>,
         voting: Mapping<(String, AccountId), VotingInfo>,  
+        option_votes: Mapping<(String, u32), Balance>,
+        poll_ids: Mapping<u32, String>,
+        poll_count: u32,
+        voter_count: Mapping<String, u32>,
+        votes_cast: Balance,
+        option_labels: Mapping<String, Vec<String>>,
+        category_polls: Mapping<(String, u32), String>,
+        category_poll_count: Mapping<String, u32>,
+        delegations: Mapping<(String, AccountId), AccountId>,
+        locked_balances: Mapping<(AccountId, AccountId), Balance>,
+        total_locked: Mapping<AccountId, Balance>,
+        allowed_voters: Mapping<(String, AccountId), bool>,
+        commitments: Mapping<(String, AccountId), Hash>,
+        voter_polls: Mapping<(AccountId, u32), String>,
+        voter_poll_count: Mapping<AccountId, u32>,
+        poll_creators: Mapping<String, AccountId>,
+        poll_statuses: Mapping<String, PollStatus>,
+        staking_contract: Option<AccountId>,
+        boost_multiplier: u32,
+        fee_bps: u16,
+        cancel_penalty_bps: u16,
+        treasury: AccountId,
+        // Lazy is written to storage immediately, so the flag is visible to reentrant calls
+        locked: Lazy<bool>,
     }
 
     // Events
@@ -44,24 +456,172 @@ mod ceres_governance {
         voter: AccountId,
         voting_option: u32,
         number_of_votes: Balance,
+        cumulative_votes: Balance,
+        received_amount: Balance,
+        timestamp: Timestamp,
     } 
 
+    #[ink(event)]
+    pub struct FeeCollected {
+        #[ink(topic)]
+        poll_id: String,
+        #[ink(topic)]
+        voter: AccountId,
+        treasury: AccountId,
+        amount: Balance,
+    }
+
+    #[ink(event)]
+    pub struct BoostApplied {
+        #[ink(topic)]
+        poll_id: String,
+        #[ink(topic)]
+        voter: AccountId,
+        number_of_votes: Balance,
+        boosted_votes: Balance,
+    }
+
+    #[ink(event)]
+    pub struct VoteCommitted {
+        #[ink(topic)]
+        poll_id: String,
+        #[ink(topic)]
+        voter: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct VoteChanged {
+        #[ink(topic)]
+        poll_id: String,
+        #[ink(topic)]
+        voter: AccountId,
+        old_option: u32,
+        new_option: u32,
+        number_of_votes: Balance,
+    }
+
+    #[ink(event)]
+    pub struct VoteDelegated {
+        #[ink(topic)]
+        poll_id: String,
+        #[ink(topic)]
+        delegator: AccountId,
+        #[ink(topic)]
+        delegate: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct VoteUndelegated {
+        #[ink(topic)]
+        poll_id: String,
+        #[ink(topic)]
+        delegator: AccountId,
+        #[ink(topic)]
+        delegate: AccountId,
+    }
+
     #[ink(event)]
     pub struct PollCreated {
         #[ink(topic)]
         poll_id: String,
         #[ink(topic)]
+        creator: AccountId,
+        #[ink(topic)]
         number_of_options: u32,
         poll_start_timestamp: Timestamp,
         poll_end_timestamp: Timestamp,
     }
 
+    #[ink(event)]
+    pub struct PollExtended {
+        #[ink(topic)]
+        poll_id: String,
+        old_end_timestamp: Timestamp,
+        new_end_timestamp: Timestamp,
+    }
+
+    #[ink(event)]
+    pub struct QuorumLowered {
+        #[ink(topic)]
+        poll_id: String,
+        old_quorum: Balance,
+        new_quorum: Balance,
+    }
+
+    #[ink(event)]
+    pub struct PollClosed {
+        #[ink(topic)]
+        poll_id: String,
+        winning_option: u32,
+        total_votes: Balance,
+    }
+
+    #[ink(event)]
+    pub struct PollCancelled {
+        #[ink(topic)]
+        poll_id: String,
+    }
+
+    #[ink(event)]
+    pub struct PollStateChanged {
+        #[ink(topic)]
+        poll_id: String,
+        old_status: PollStatus,
+        new_status: PollStatus,
+    }
+
+    #[ink(event)]
+    pub struct PollMetadataUpdated {
+        #[ink(topic)]
+        poll_id: String,
+        title: String,
+        description: String,
+    }
+
     #[ink(event)]
     pub struct FundsWithdrawn {
+        #[ink(topic)]
+        poll_id: String,
         #[ink(topic)]
         voter: AccountId,
         #[ink(topic)]
         amount: Balance,
+        quorum_reached: bool,
+        timestamp: Timestamp,
+    }
+
+    #[ink(event)]
+    pub struct ExcessSwept {
+        #[ink(topic)]
+        token: AccountId,
+        #[ink(topic)]
+        to: AccountId,
+        amount: Balance,
+    }
+
+    #[ink(event)]
+    pub struct AdminActionProposed {
+        #[ink(topic)]
+        proposal_id: u32,
+        #[ink(topic)]
+        proposer: AccountId,
+        action: AdminAction,
+    }
+
+    #[ink(event)]
+    pub struct AdminActionExecuted {
+        #[ink(topic)]
+        proposal_id: u32,
+    }
+
+    #[ink(event)]
+    pub struct CodeUpgraded {
+        code_hash: Hash,
+    }
+
+    #[ink(event)]
+    pub struct PausedStateChanged {
+        paused: bool,
     }
 
     #[derive(Debug, PartialEq, Eq, Encode, Decode)]
@@ -93,32 +653,180 @@ mod ceres_governance {
         PollIdAlreadyExists,
         /// Poll does not exist
         PollDoesNotExist,
+        /// Token transfer failed, carrying the error reported by the token
+        TokenTransferFailed(PSP22Error),
+        /// Voter has not voted
+        VoterHasNotVoted,
+        /// Poll is already closed
+        PollAlreadyClosed,
+        /// Caller is not authorized
+        NotAuthorized,
+        /// Contract is paused
+        ContractPaused,
+        /// Quorum is not reached
+        QuorumNotReached,
+        /// Invalid option labels
+        InvalidOptionLabels,
+        /// Title or description is too long
+        MetadataTooLong,
+        /// Voter already withdrew funds from the poll
+        AlreadyWithdrawn,
+        /// Poll is cancelled
+        PollCancelled,
+        /// Invalid delegation
+        InvalidDelegation,
+        /// Token query failed
+        TokenQueryFailed,
+        /// Maximum votes per voter exceeded
+        MaxVotesExceeded,
+        /// Batch is too large
+        BatchTooLarge,
+        /// Arithmetic overflow
+        ArithmeticOverflow,
+        /// Reentrant call detected
+        ReentrancyDetected,
+        /// Invalid token address
+        InvalidToken,
+        /// Voter is not allowed to vote on the poll
+        VoterNotAllowed,
+        /// Poll duration is too long
+        PollDurationTooLong,
+        /// Poll duration is too short
+        PollDurationTooShort,
+        /// Withdrawal delay after the poll end has not passed yet
+        WithdrawalNotYetAvailable,
+        /// Revealed vote does not match the commitment
+        CommitmentMismatch,
+        /// Reveal window is closed
+        RevealWindowClosed,
+        /// Boost multiplier is below 1x
+        InvalidBoostMultiplier,
+        /// Staking contract query failed
+        StakingQueryFailed,
+        /// Fee is above 100%
+        InvalidFee,
+        /// Poll has already started
+        PollAlreadyStarted,
+        /// Poll id is longer than MAX_POLL_ID_LENGTH
+        PollIdTooLong,
+        /// Poll id is empty, only whitespace or contains control characters
+        InvalidPollId,
+        /// Poll is not cancelled
+        PollNotCancelled,
+        /// Admin action needs more confirmations before it can run
+        InsufficientConfirmations,
+        /// Admin proposal does not exist
+        AdminProposalNotFound,
+        /// Admin already confirmed the proposal
+        AlreadyConfirmed,
+        /// Admin set or threshold is invalid
+        InvalidAdmins,
+        /// Voter has not approved enough tokens to the contract
+        InsufficientAllowance,
+        /// Quorum can only be lowered
+        InvalidQuorum,
+        /// Replacing the contract code failed
+        CodeUpgradeFailed,
+        /// Vote would take the poll past its total vote cap
+        PollVoteCapReached,
+        /// Caller is neither the poll creator nor the contract owner
+        NotPollCreator,
+        /// Poll has more options than allowed
+        TooManyOptions,
+        /// Token transfer delivered nothing to the contract
+        NothingReceived,
+        /// Caller holds less CERES than required to create a poll
+        InsufficientBalanceToCreate,
+        /// Poll is a draft and not open for voting yet
+        PollIsDraft,
+        /// Leading options are tied and the poll has no winner
+        TieUnresolved,
+        /// Leading options are tied and the poll has to be extended
+        TieRequiresExtension,
+        /// Poll has fewer distinct voters than its voter quorum
+        VoterQuorumNotReached,
+        /// Number of votes is not a multiple of the poll's vote step
+        InvalidVoteStep,
+        /// Reveal window is longer than the maximum poll duration
+        RevealWindowTooLong,
+        /// Withdrawal delay is longer than the maximum poll duration
+        WithdrawalDelayTooLong,
+        /// Maximum poll duration is 0 or below the minimum poll duration
+        InvalidPollDurationBounds,
     }
     
     impl CeresGovernance {
 
         #[ink(constructor)]
         // Creat a new instance of the contract passing the address of the Ceres token
-        pub fn new() -> Self {
+        pub fn new(ceres_token: AccountId) -> Self {
             Self {
+                admins: ink::prelude::vec![Self::env().caller()],
+                threshold: 1,
+                admin_proposals: Mapping::new(),
+                next_proposal_id: 0,
+                executing: false,
+                ceres_token,
+                paused: false,
+                max_poll_duration: DEFAULT_MAX_POLL_DURATION,
+                min_poll_duration: DEFAULT_MIN_POLL_DURATION,
+                max_options: DEFAULT_MAX_OPTIONS,
+                min_balance_to_create: 0,
                 poll_data: Mapping::new(),
                 voting: Mapping::new(),  
+                option_votes: Mapping::new(),
+                poll_ids: Mapping::new(),
+                poll_count: 0,
+                voter_count: Mapping::new(),
+                votes_cast: 0,
+                option_labels: Mapping::new(),
+                category_polls: Mapping::new(),
+                category_poll_count: Mapping::new(),
+                delegations: Mapping::new(),
+                locked_balances: Mapping::new(),
+                total_locked: Mapping::new(),
+                allowed_voters: Mapping::new(),
+                commitments: Mapping::new(),
+                voter_polls: Mapping::new(),
+                voter_poll_count: Mapping::new(),
+                poll_creators: Mapping::new(),
+                poll_statuses: Mapping::new(),
+                staking_contract: None,
+                boost_multiplier: BOOST_DENOMINATOR,
+                fee_bps: 0,
+                cancel_penalty_bps: 0,
+                treasury: Self::env().caller(),
+                locked: Lazy::new(),
             }
         }
 
         #[ink(message)]
+        #[allow(clippy::too_many_arguments)]
         pub fn create_poll(
             &mut self,
             poll_id: String,
             number_of_options: u32,
             poll_start_timestamp: Timestamp,
             poll_end_timestamp: Timestamp,
+            settings: PollSettings,
+            option_labels: Vec<String>,
+            title: String,
+            description: String,
         ) -> Result<(), Error> {
+            self.ensure_owner()?;
+
+            // Whitespace-only ids and control characters would show up as confusing duplicates in UIs
+            if poll_id.trim().is_empty() || poll_id.chars().any(char::is_control) {
+                return Err(Error::InvalidPollId)
+            }
+
+            if poll_id.len() > MAX_POLL_ID_LENGTH {
+                return Err(Error::PollIdTooLong)
+            }
 
-            let current_timestamp = self.env().block_timestamp();
-            let poll_info = self.poll_data.get(&poll_id).unwrap_or_default();
+            let current_timestamp = self.now(settings.window);
 
-            if poll_info.number_of_options != 0 {
+            if self.poll_data.contains(&poll_id) {
                 return Err(Error::PollIdAlreadyExists);
             }
             
@@ -126,6 +834,16 @@ mod ceres_governance {
                 return Err(Error::InvalidNumberOfOption)
             }
 
+            if number_of_options > self.max_options {
+                return Err(Error::TooManyOptions)
+            }
+
+            if self.min_balance_to_create > 0
+                && self.balance_of(self.ceres_token, self.env().caller())? < self.min_balance_to_create
+            {
+                return Err(Error::InsufficientBalanceToCreate)
+            }
+
             if poll_start_timestamp < current_timestamp {
                 return Err(Error::InvalidStartTimestamp)
             }
@@ -134,16 +852,96 @@ mod ceres_governance {
                 return Err(Error::InvalidEndTimestamp)
             }
 
+            let (min_duration, max_duration) = self.duration_bounds(settings.window);
+
+            if poll_end_timestamp - poll_start_timestamp > max_duration {
+                return Err(Error::PollDurationTooLong)
+            }
+
+            if poll_end_timestamp - poll_start_timestamp < min_duration {
+                return Err(Error::PollDurationTooShort)
+            }
+
+            if settings.reveal_end_timestamp != 0 && settings.reveal_end_timestamp <= poll_end_timestamp {
+                return Err(Error::InvalidEndTimestamp)
+            }
+
+            // A reveal window is bounded like the poll itself, it keeps the committed funds locked
+            if settings.reveal_end_timestamp != 0
+                && settings.reveal_end_timestamp - poll_end_timestamp > max_duration
+            {
+                return Err(Error::RevealWindowTooLong)
+            }
+
+            // Funds stay locked for the delay, so it is bounded like the poll itself
+            if settings.withdrawal_delay > max_duration {
+                return Err(Error::WithdrawalDelayTooLong)
+            }
+
+            if option_labels.len() != number_of_options as usize {
+                return Err(Error::InvalidOptionLabels)
+            }
+
+            if option_labels.iter().any(|label| label.len() > MAX_OPTION_LABEL_LENGTH) {
+                return Err(Error::InvalidOptionLabels)
+            }
+
+            if option_labels.iter().map(|label| label.len()).sum::<usize>() > MAX_OPTION_LABELS_LENGTH {
+                return Err(Error::InvalidOptionLabels)
+            }
+
+            if title.len() > MAX_TITLE_LENGTH || description.len() > MAX_DESCRIPTION_LENGTH {
+                return Err(Error::MetadataTooLong)
+            }
+
+            if settings.category.len() > MAX_CATEGORY_LENGTH {
+                return Err(Error::MetadataTooLong)
+            }
+
+            if settings.token == Some(AccountId::from([0u8; 32])) {
+                return Err(Error::InvalidToken)
+            }
+
+            // Every vote of a one vote per account poll is exactly 1, larger bounds would refuse them all
+            if settings.voting_mode == VotingMode::OnePerAccount {
+                if settings.min_votes > 1 {
+                    return Err(Error::InvalidNumberOfVotes)
+                }
+
+                if settings.vote_step > 1 {
+                    return Err(Error::InvalidVoteStep)
+                }
+            }
+
             let poll_info = PollInfo {
                 number_of_options,
                 poll_start_timestamp,
                 poll_end_timestamp,
+                closed: false,
+                title,
+                description,
+                cancelled: false,
+                snapshot_block: self.env().block_number(),
+                settings,
             };
 
             self.poll_data.insert(&poll_id, &poll_info);
+            self.poll_ids.insert(self.poll_count, &poll_id);
+            self.poll_count = self.poll_count.checked_add(1).ok_or(Error::ArithmeticOverflow)?;
+            self.option_labels.insert(&poll_id, &option_labels);
+
+            // A poll created through an admin proposal belongs to the contract, not to the last confirmer
+            let creator = if self.executing { self.env().account_id() } else { self.env().caller() };
+            self.poll_creators.insert(&poll_id, &creator);
+
+            if !poll_info.settings.category.is_empty() {
+                self.record_category_poll(&poll_info.settings.category, &poll_id)?;
+            }
+            self.poll_statuses.insert(&poll_id, &self.status_of(&poll_info));
 
             self.env().emit_event(PollCreated {
                 poll_id: poll_id.clone(),
+                creator,
                 number_of_options,
                 poll_start_timestamp,
                 poll_end_timestamp,
@@ -152,94 +950,413 @@ mod ceres_governance {
             Ok(())
         }
 
+        // Same as create_poll with the id derived from the caller, the poll count and the block number
+        #[ink(message)]
+        #[allow(clippy::too_many_arguments)]
+        pub fn create_poll_auto(
+            &mut self,
+            number_of_options: u32,
+            poll_start_timestamp: Timestamp,
+            poll_end_timestamp: Timestamp,
+            settings: PollSettings,
+            option_labels: Vec<String>,
+            title: String,
+            description: String,
+        ) -> Result<String, Error> {
+            let poll_id = self.generate_poll_id();
+
+            self.create_poll(
+                poll_id.clone(),
+                number_of_options,
+                poll_start_timestamp,
+                poll_end_timestamp,
+                settings,
+                option_labels,
+                title,
+                description,
+            )?;
+
+            Ok(poll_id)
+        }
+
         #[ink(message)]
         pub fn vote(
             &mut self,
             poll_id: String,
             voting_option: u32,
             number_of_votes: Balance,
-        ) -> Result<(), Error>{
+        ) -> Result<VotingInfo, Error> {
             let caller = self.env().caller();
 
-            if number_of_votes <= 0 {
-                return Err(Error::InvalidNumberOfVotes)
-            }
-
-            let poll_info = self.poll_data.get(&poll_id).unwrap_or_default();
-            let current_timestamp = self.env().block_timestamp();       
-
-            if current_timestamp < poll_info.poll_start_timestamp {
-                return Err(Error::PollIsNotStarted)
-            }
+            self.cast_vote(caller, poll_id, voting_option, number_of_votes, false)
+        }
 
-            if current_timestamp > poll_info.poll_end_timestamp {
-                return Err(Error::PollIsFinished);
-            }
-    
-            if voting_option > poll_info.number_of_options{
-                return Err(Error::InvalidNumberOfOption)
-            }
+        // Preview the voter state a vote would produce, including the protocol fee, without sending it
+        #[ink(message)]
+        pub fn simulate_vote(
+            &self,
+            poll_id: String,
+            voting_option: u32,
+            number_of_votes: Balance,
+            voter: AccountId,
+        ) -> Result<VotingInfo, Error> {
+            Ok(self.prepare_vote(voter, &poll_id, voting_option, number_of_votes, false)?.voting_info)
+        }
 
-            let mut voting_info = self.voting.get(&(poll_id.clone(), caller)).unwrap_or_default();
+        // Amount a withdrawal would return to the voter, or the reason it would fail, without sending it
+        #[ink(message)]
+        pub fn preview_withdraw(
+            &self,
+            poll_id: String,
+            voter: AccountId,
+        ) -> Result<Balance, Error> {
+            let (poll_info, voting_info) = self.check_withdrawal(voter, &poll_id)?;
+            let penalty = self.cancel_penalty_of(&poll_info, voting_info.locked_amount)?;
 
-            if voting_info.voting_option == 0 {
-                voting_info.voting_option = voting_option;                
-            } else {
-                if voting_info.voting_option != voting_option {
-                    return Err(Error::VoteDenied)
-                }
-            }
+            Ok(voting_info.locked_amount - penalty)
+        }
 
-            voting_info.number_of_votes += number_of_votes;    
-                
-            self.voting.insert(&(poll_id.clone(), caller), &voting_info); 
+        // Refundable polls among the voter's most recent MAX_PAGE_SIZE polls, which keeps the cost bounded
+        #[ink(message)]
+        pub fn pending_withdrawals(&self, voter: AccountId) -> Vec<(String, Balance)> {
+            let poll_count = self.voter_poll_count.get(voter).unwrap_or_default();
 
-            self.env().emit_event(Voted {
-                poll_id: poll_id.clone(),
-                voter: caller,
-                voting_option,
-                number_of_votes,
-            });           
+            (poll_count.saturating_sub(MAX_PAGE_SIZE)..poll_count)
+                .rev()
+                .filter_map(|index| self.voter_polls.get((voter, index)))
+                .filter_map(|poll_id| {
+                    let amount = self.preview_withdraw(poll_id.clone(), voter).ok()?;
+                    (amount > 0).then_some((poll_id, amount))
+                })
+                .collect()
+        }
 
-            Ok(().into())
+        // Whether a withdrawal by the voter would currently succeed
+        #[ink(message)]
+        pub fn can_withdraw(
+            &self,
+            poll_id: String,
+            voter: AccountId,
+        ) -> bool {
+            self.check_withdrawal(voter, &poll_id).is_ok()
         }
 
+        // Returning an error reverts the whole batch, so no vote is applied unless all succeed
         #[ink(message)]
-        pub fn withdrawn(
+        pub fn vote_batch(
             &mut self,
-            poll_id: String,
+            votes: Vec<(String, u32, Balance)>,
         ) -> Result<(), Error> {
             let caller = self.env().caller();
-            let poll_info = self.poll_data.get(&poll_id).unwrap_or_default();
-            let current_timestamp = self.env().block_timestamp();
 
-            if poll_info.number_of_options == 0 {
-                return Err(Error::PollDoesNotExist)
+            if votes.len() > MAX_BATCH_SIZE {
+                return Err(Error::BatchTooLarge)
             }
 
-            if current_timestamp < poll_info.poll_end_timestamp {
-                return Err(Error::PollIsNotFinished)
+            for (poll_id, voting_option, number_of_votes) in votes {
+                self.cast_vote(caller, poll_id, voting_option, number_of_votes, false)?;
             }
 
-            let mut voting_info = self.voting.get(&(poll_id.clone(), caller)).unwrap_or_default();
+            Ok(())
+        }
 
-            if voting_info.number_of_votes == 0 {
-                return Err(Error::InvalidVotes)
+        // Split votes across several options of a MultiChoice poll, locking their sum
+        #[ink(message)]
+        pub fn vote_weighted(
+            &mut self,
+            poll_id: String,
+            allocations: Vec<(u32, Balance)>,
+        ) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            self.guarded(|this| this.apply_weighted_vote(caller, poll_id, allocations))
+        }
+
+        // Vote on behalf of an account that delegated its vote to the caller
+        #[ink(message)]
+        pub fn vote_for(
+            &mut self,
+            poll_id: String,
+            delegator: AccountId,
+            voting_option: u32,
+            number_of_votes: Balance,
+        ) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            if self.delegations.get(&(poll_id.clone(), delegator)) != Some(caller) {
+                return Err(Error::InvalidDelegation)
             }
 
-            if voting_info.ceres_withdrawn == true {
-                return Err(Error::FundsAlreadyWithdrawn)
+            self.cast_vote(delegator, poll_id, voting_option, number_of_votes, false)?;
+
+            Ok(())
+        }
+
+        // The commitment is the Blake2x256 hash of the SCALE encoded (option, salt) pair
+        #[ink(message)]
+        pub fn commit_vote(
+            &mut self,
+            poll_id: String,
+            commitment: Hash,
+        ) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            if self.paused {
+                return Err(Error::ContractPaused)
+            }
+
+            let poll_info = self.load_poll(&poll_id)?;
+
+            self.check_active(&poll_info)?;
+
+            if poll_info.settings.reveal_end_timestamp == 0 {
+                return Err(Error::VoteDenied)
+            }
+
+            if !self.is_voter_allowed(&poll_id, &poll_info, caller) {
+                return Err(Error::VoterNotAllowed)
+            }
+
+            self.commitments.insert(&(poll_id.clone(), caller), &commitment);
+
+            self.env().emit_event(VoteCommitted {
+                poll_id: poll_id.clone(),
+                voter: caller,
+            });
+
+            Ok(())
+        }
+
+        // Votes committed during the poll are revealed and counted after the poll end
+        #[ink(message)]
+        pub fn reveal_vote(
+            &mut self,
+            poll_id: String,
+            option: u32,
+            salt: [u8; 32],
+            number_of_votes: Balance,
+        ) -> Result<(), Error> {
+            let caller = self.env().caller();
+            let poll_info = self.load_poll(&poll_id)?;
+            let current_timestamp = self.now(poll_info.settings.window);
+
+            if current_timestamp <= poll_info.poll_end_timestamp {
+                return Err(Error::PollIsNotFinished)
+            }
+
+            if current_timestamp > poll_info.settings.reveal_end_timestamp {
+                return Err(Error::RevealWindowClosed)
+            }
+
+            let commitment = self.commitments.get(&(poll_id.clone(), caller)).ok_or(Error::VoterHasNotVoted)?;
+
+            let mut hash = <Blake2x256 as ink::env::hash::HashOutput>::Type::default();
+            ink::env::hash_encoded::<Blake2x256, _>(&(option, salt), &mut hash);
+
+            if commitment != Hash::from(hash) {
+                return Err(Error::CommitmentMismatch)
+            }
+
+            self.commitments.remove(&(poll_id.clone(), caller));
+
+            self.cast_vote(caller, poll_id, option, number_of_votes, true)?;
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn delegate(
+            &mut self,
+            poll_id: String,
+            delegate_to: AccountId,
+        ) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            self.load_poll(&poll_id)?;
+
+            if delegate_to == caller {
+                return Err(Error::InvalidDelegation)
+            }
+
+            // Follow the chain of the new delegate to make sure it never leads back to the caller
+            let mut current = delegate_to;
+            for _ in 0..MAX_DELEGATION_DEPTH {
+                match self.delegations.get(&(poll_id.clone(), current)) {
+                    Some(next) if next == caller => return Err(Error::InvalidDelegation),
+                    Some(next) => current = next,
+                    None => break,
+                }
+            }
+
+            self.delegations.insert(&(poll_id.clone(), caller), &delegate_to);
+
+            self.env().emit_event(VoteDelegated {
+                poll_id: poll_id.clone(),
+                delegator: caller,
+                delegate: delegate_to,
+            });
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn undelegate(
+            &mut self,
+            poll_id: String,
+        ) -> Result<(), Error> {
+            let caller = self.env().caller();
+            let delegate = self.delegations.get(&(poll_id.clone(), caller)).ok_or(Error::InvalidDelegation)?;
+
+            self.delegations.remove(&(poll_id.clone(), caller));
+
+            self.env().emit_event(VoteUndelegated {
+                poll_id: poll_id.clone(),
+                delegator: caller,
+                delegate,
+            });
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn change_vote(
+            &mut self,
+            poll_id: String,
+            new_option: u32,
+        ) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            if self.paused {
+                return Err(Error::ContractPaused)
+            }
+
+            let poll_info = self.load_poll(&poll_id)?;
+
+            self.check_active(&poll_info)?;
+
+            if poll_info.settings.voting_mode == VotingMode::MultiChoice {
+                return Err(Error::VoteDenied)
+            }
+
+            if !Self::is_valid_option(&poll_info, new_option) {
+                return Err(Error::InvalidNumberOfOption)
+            }
+
+            let mut voting_info = self.voting.get(&(poll_id.clone(), caller)).unwrap_or_default();
+
+            if !voting_info.has_voted {
+                return Err(Error::VoterHasNotVoted)
+            }
+
+            if voting_info.ceres_withdrawn {
+                return Err(Error::AlreadyWithdrawn)
+            }
+
+            let old_option = voting_info.voting_option;
+
+            if old_option == new_option {
+                return Err(Error::VoteDenied)
             }
 
-            voting_info.ceres_withdrawn = true;
+            let old_option_votes = self.option_votes.get(&(poll_id.clone(), old_option)).unwrap_or_default();
+            self.option_votes.insert(&(poll_id.clone(), old_option), &old_option_votes.checked_sub(voting_info.tally_votes).ok_or(Error::ArithmeticOverflow)?);
+
+            let new_option_votes = self.option_votes.get(&(poll_id.clone(), new_option)).unwrap_or_default();
+            self.option_votes.insert(&(poll_id.clone(), new_option), &new_option_votes.checked_add(voting_info.tally_votes).ok_or(Error::ArithmeticOverflow)?);
+
+            voting_info.voting_option = new_option;
             self.voting.insert(&(poll_id.clone(), caller), &voting_info);
 
-            self.env().emit_event(FundsWithdrawn {
+            self.observe_status(&poll_id, &poll_info);
+
+            self.env().emit_event(VoteChanged {
+                poll_id: poll_id.clone(),
                 voter: caller,
-                amount: voting_info.number_of_votes,
+                old_option,
+                new_option,
+                number_of_votes: voting_info.number_of_votes,
             });
 
-            Ok(().into())
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn withdrawn(
+            &mut self,
+            poll_id: String,
+        ) -> Result<(), Error> {
+            let caller = self.env().caller();
+            self.withdraw_funds(caller, poll_id)?;
+
+            Ok(())
+        }
+
+        // Anyone can trigger the withdrawal, the CERES always goes back to the voter
+        #[ink(message)]
+        pub fn withdraw_for(
+            &mut self,
+            poll_id: String,
+            voter: AccountId,
+        ) -> Result<(), Error> {
+            self.withdraw_funds(voter, poll_id)?;
+
+            Ok(())
+        }
+
+        // Votes stay counted, only the locked CERES is reduced
+        #[ink(message)]
+        pub fn withdraw_partial(
+            &mut self,
+            poll_id: String,
+            amount: Balance,
+        ) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            self.guarded(|this| this.release_partial(caller, poll_id, amount))
+        }
+
+        // Polls the caller already withdrew from or never voted on are skipped, any other failure reverts the batch
+        #[ink(message)]
+        pub fn withdraw_batch(
+            &mut self,
+            poll_ids: Vec<String>,
+        ) -> Result<Balance, Error> {
+            let caller = self.env().caller();
+
+            if poll_ids.len() > MAX_BATCH_SIZE {
+                return Err(Error::BatchTooLarge)
+            }
+
+            let mut total_amount: Balance = 0;
+
+            for poll_id in poll_ids {
+                // Checked up front so polls with nothing to withdraw are skipped instead of failing the batch
+                match self.check_withdrawal(caller, &poll_id) {
+                    Err(Error::FundsAlreadyWithdrawn) | Err(Error::InvalidVotes) => continue,
+                    Err(error) => return Err(error),
+                    Ok(_) => {}
+                }
+
+                let (amount, _) = self.withdraw_funds(caller, poll_id)?;
+                total_amount = total_amount.checked_add(amount).ok_or(Error::ArithmeticOverflow)?;
+            }
+
+            Ok(total_amount)
+        }
+
+        #[ink(message)]
+        pub fn withdraw_with_status(
+            &mut self,
+            poll_id: String,
+        ) -> Result<WithdrawOutcome, Error> {
+            let caller = self.env().caller();
+            let (amount, quorum_reached) = self.withdraw_funds(caller, poll_id)?;
+
+            if quorum_reached {
+                Ok(WithdrawOutcome::QuorumReached(amount))
+            } else {
+                Ok(WithdrawOutcome::QuorumFailed(amount))
+            }
         }
 
         #[ink(message)]
@@ -247,14 +1364,3831 @@ mod ceres_governance {
             &self,
             poll_id: String,
         ) -> Result<PollInfo, Error> {
-            let poll_info = self.poll_data.get(&poll_id).unwrap_or_default();
+            self.load_poll(&poll_id)
+        } 
+
+        // Only the first MAX_BATCH_SIZE ids are looked up
+        #[ink(message)]
+        pub fn get_poll_infos(
+            &self,
+            poll_ids: Vec<String>,
+        ) -> Vec<Result<PollInfo, Error>> {
+            poll_ids
+                .into_iter()
+                .take(MAX_BATCH_SIZE)
+                .map(|poll_id| self.get_poll_info(poll_id))
+                .collect()
+        }
+
+        // Remaining milliseconds, or blocks for a block number window
+        #[ink(message)]
+        pub fn time_until_end(
+            &self,
+            poll_id: String,
+        ) -> Result<Timestamp, Error> {
+            let poll_info = self.load_poll(&poll_id)?;
+
+            Ok(poll_info.poll_end_timestamp.saturating_sub(self.now(poll_info.settings.window)))
+        }
+
+        #[ink(message)]
+        pub fn poll_status(
+            &self,
+            poll_id: String,
+        ) -> Result<PollStatus, Error> {
+            let poll_info = self.load_poll(&poll_id)?;
+
+            Ok(self.status_of(&poll_info))
+        }
+
+        #[ink(message)]
+        pub fn get_poll_options(
+            &self,
+            poll_id: String,
+        ) -> Result<Vec<String>, Error> {
+            self.load_poll(&poll_id)?;
+
+            Ok(self.option_labels.get(&poll_id).unwrap_or_default())
+        }
+
+        #[ink(message)]
+        pub fn get_voting_info(
+            &self,
+            poll_id: String,
+            voter: AccountId,
+        ) -> Result<VotingInfo, Error> {
+            self.load_poll(&poll_id)?;
+
+            self.voting.get(&(poll_id, voter)).ok_or(Error::VoterHasNotVoted)
+        }
+
+        // Option, votes and withdrawn flag of the voter, for tooling that does not decode VotingInfo
+        #[ink(message)]
+        pub fn last_vote_summary(
+            &self,
+            poll_id: String,
+            voter: AccountId,
+        ) -> Result<(u32, Balance, bool), Error> {
+            let voting_info = self.get_voting_info(poll_id, voter)?;
+
+            Ok((voting_info.voting_option, voting_info.number_of_votes, voting_info.ceres_withdrawn))
+        }
+
+        // Only the first MAX_BATCH_SIZE voters are looked up
+        #[ink(message)]
+        pub fn get_voting_infos(
+            &self,
+            poll_id: String,
+            voters: Vec<AccountId>,
+        ) -> Vec<Option<VotingInfo>> {
+            voters
+                .into_iter()
+                .take(MAX_BATCH_SIZE)
+                .map(|voter| self.voting.get(&(poll_id.clone(), voter)))
+                .collect()
+        }
+
+        #[ink(message)]
+        pub fn has_voted(
+            &self,
+            poll_id: String,
+            voter: AccountId,
+        ) -> bool {
+            self.voting
+                .get(&(poll_id, voter))
+                .is_some_and(|voting_info| voting_info.number_of_votes > 0)
+        }
+
+        // True while a committed vote still has to be revealed
+        #[ink(message)]
+        pub fn has_committed(
+            &self,
+            poll_id: String,
+            voter: AccountId,
+        ) -> bool {
+            self.commitments.contains(&(poll_id, voter))
+        }
+
+        // Tokens of the voter currently locked across polls using the given token
+        #[ink(message)]
+        pub fn get_locked_balance(
+            &self,
+            voter: AccountId,
+            token: AccountId,
+        ) -> Balance {
+            self.locked_balances.get((voter, token)).unwrap_or_default()
+        }
+
+        #[ink(message)]
+        pub fn get_total_locked(
+            &self,
+            token: AccountId,
+        ) -> Balance {
+            self.total_locked.get(token).unwrap_or_default()
+        }
+
+        // Default token held by the contract next to the amount its accounting tracks as locked,
+        // a failed balance query reports a held balance of 0
+        #[ink(message)]
+        pub fn reconcile(&self) -> (Balance, Balance) {
+            let balance = self.balance_of(self.ceres_token, self.env().account_id()).unwrap_or_default();
 
-            if poll_info.number_of_options == 0 {
-                return Err(Error::PollDoesNotExist)
+            (balance, self.total_locked.get(self.ceres_token).unwrap_or_default())
+        }
+
+        #[ink(message)]
+        pub fn get_option_votes(
+            &self,
+            poll_id: String,
+            option: u32,
+        ) -> Result<Balance, Error> {
+            let poll_info = self.load_poll(&poll_id)?;
+
+            if !Self::is_valid_option(&poll_info, option) {
+                return Err(Error::InvalidNumberOfOption)
             }
 
-            Ok(poll_info)
-        } 
-        
+            Ok(self.option_votes.get(&(poll_id, option)).unwrap_or_default())
+        }
+
+        #[ink(message)]
+        pub fn get_voter_count(
+            &self,
+            poll_id: String,
+        ) -> Result<u32, Error> {
+            self.load_poll(&poll_id)?;
+
+            Ok(self.voter_count.get(&poll_id).unwrap_or_default())
+        }
+
+        #[ink(message)]
+        pub fn get_poll_creator(
+            &self,
+            poll_id: String,
+        ) -> Result<AccountId, Error> {
+            self.poll_creators.get(&poll_id).ok_or(Error::PollDoesNotExist)
+        }
+
+        #[ink(message)]
+        pub fn get_total_votes(
+            &self,
+            poll_id: String,
+        ) -> Result<Balance, Error> {
+            let poll_info = self.load_poll(&poll_id)?;
+
+            self.total_votes(&poll_id, &poll_info)
+        }
+
+        #[ink(message)]
+        pub fn get_poll_result(
+            &self,
+            poll_id: String,
+        ) -> Result<(u32, Balance), Error> {
+            let poll_info = self.load_poll(&poll_id)?;
+
+            self.check_finished(&poll_info)?;
+
+            if self.total_votes(&poll_id, &poll_info)? < poll_info.settings.quorum {
+                return Err(Error::QuorumNotReached)
+            }
+
+            if self.voter_count.get(&poll_id).unwrap_or_default() < poll_info.settings.min_unique_voters {
+                return Err(Error::VoterQuorumNotReached)
+            }
+
+            let (winning_option, winning_votes) = self.winning_option(&poll_id, &poll_info);
+
+            if self.is_tied(&poll_id, &poll_info, winning_votes) {
+                match poll_info.settings.tie_break {
+                    TieBreak::LowestIndex => {}
+                    TieBreak::NoWinner => return Err(Error::TieUnresolved),
+                    TieBreak::RequireExtension => return Err(Error::TieRequiresExtension),
+                }
+            }
+
+            Ok((winning_option, winning_votes))
+        }
+
+        // Live leader while the poll is still running, (0, 0) before any vote is counted
+        #[ink(message)]
+        pub fn get_leading_option(
+            &self,
+            poll_id: String,
+        ) -> Result<(u32, Balance), Error> {
+            let poll_info = self.load_poll(&poll_id)?;
+            let (leading_option, leading_votes) = self.winning_option(&poll_id, &poll_info);
+
+            if leading_votes == 0 {
+                return Ok((0, 0))
+            }
+
+            Ok((leading_option, leading_votes))
+        }
+
+        // Vote count of every option, zero-vote options included
+        #[ink(message)]
+        pub fn get_poll_breakdown(
+            &self,
+            poll_id: String,
+        ) -> Result<Vec<(u32, Balance)>, Error> {
+            let poll_info = self.load_poll(&poll_id)?;
+
+            self.check_finished(&poll_info)?;
+
+            Ok((1..=poll_info.number_of_options)
+                .map(|option| (option, self.option_votes.get(&(poll_id.clone(), option)).unwrap_or_default()))
+                .collect())
+        }
+
+        #[ink(message)]
+        pub fn is_quorum_reached(
+            &self,
+            poll_id: String,
+        ) -> Result<bool, Error> {
+            let poll_info = self.load_poll(&poll_id)?;
+
+            self.quorum_reached(&poll_id, &poll_info)
+        }
+
+        // Open a draft poll, starting it now unless its preset start is still ahead
+        #[ink(message)]
+        pub fn activate_poll(
+            &mut self,
+            poll_id: String,
+        ) -> Result<(), Error> {
+            self.ensure_owner_or_creator(&poll_id)?;
+
+            let mut poll_info = self.load_poll(&poll_id)?;
+
+            if poll_info.cancelled {
+                return Err(Error::PollCancelled)
+            }
+
+            if !poll_info.settings.draft {
+                return Err(Error::PollAlreadyStarted)
+            }
+
+            let poll_start_timestamp = poll_info.poll_start_timestamp.max(self.now(poll_info.settings.window));
+
+            if poll_info.poll_end_timestamp <= poll_start_timestamp {
+                return Err(Error::InvalidEndTimestamp)
+            }
+
+            // A later start shortens the poll, it still has to last the minimum duration
+            let (min_duration, _) = self.duration_bounds(poll_info.settings.window);
+
+            if poll_info.poll_end_timestamp - poll_start_timestamp < min_duration {
+                return Err(Error::PollDurationTooShort)
+            }
+
+            poll_info.settings.draft = false;
+            poll_info.poll_start_timestamp = poll_start_timestamp;
+            self.poll_data.insert(&poll_id, &poll_info);
+            self.observe_status(&poll_id, &poll_info);
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn extend_poll(
+            &mut self,
+            poll_id: String,
+            new_end_timestamp: Timestamp,
+        ) -> Result<(), Error> {
+            self.ensure_owner_or_creator(&poll_id)?;
+
+            let mut poll_info = self.load_poll(&poll_id)?;
+
+            if poll_info.cancelled {
+                return Err(Error::PollCancelled)
+            }
+
+            // A finished poll can only be extended to break a tie that requires it
+            match self.status_of(&poll_info) {
+                PollStatus::Closed => return Err(Error::PollIsFinished),
+                PollStatus::Finished if !self.requires_extension(&poll_id, &poll_info) => return Err(Error::PollIsFinished),
+                _ => {}
+            }
+
+            if new_end_timestamp <= poll_info.poll_end_timestamp {
+                return Err(Error::InvalidEndTimestamp)
+            }
+
+            let (_, max_duration) = self.duration_bounds(poll_info.settings.window);
+
+            if new_end_timestamp - poll_info.poll_start_timestamp > max_duration {
+                return Err(Error::PollDurationTooLong)
+            }
+
+            let old_end_timestamp = poll_info.poll_end_timestamp;
+
+            // The reveal window moves with the end, otherwise committed votes could never be revealed
+            if poll_info.settings.reveal_end_timestamp != 0 {
+                let reveal_end_timestamp = poll_info.settings.reveal_end_timestamp
+                    .checked_add(new_end_timestamp - old_end_timestamp)
+                    .ok_or(Error::ArithmeticOverflow)?;
+
+                if reveal_end_timestamp - new_end_timestamp > max_duration {
+                    return Err(Error::RevealWindowTooLong)
+                }
+
+                poll_info.settings.reveal_end_timestamp = reveal_end_timestamp;
+            }
+
+            poll_info.poll_end_timestamp = new_end_timestamp;
+            self.poll_data.insert(&poll_id, &poll_info);
+
+            self.env().emit_event(PollExtended {
+                poll_id: poll_id.clone(),
+                old_end_timestamp,
+                new_end_timestamp,
+            });
+
+            Ok(())
+        }
+
+        // The quorum can only be lowered, and only until the poll finishes
+        #[ink(message)]
+        pub fn lower_quorum(
+            &mut self,
+            poll_id: String,
+            new_quorum: Balance,
+        ) -> Result<(), Error> {
+            self.ensure_owner()?;
+
+            let mut poll_info = self.load_poll(&poll_id)?;
+
+            match self.status_of(&poll_info) {
+                PollStatus::Cancelled => return Err(Error::PollCancelled),
+                PollStatus::Finished | PollStatus::Closed => return Err(Error::PollIsFinished),
+                PollStatus::Draft | PollStatus::NotStarted | PollStatus::Active => {}
+            }
+
+            if new_quorum >= poll_info.settings.quorum {
+                return Err(Error::InvalidQuorum)
+            }
+
+            let old_quorum = poll_info.settings.quorum;
+            poll_info.settings.quorum = new_quorum;
+            self.poll_data.insert(&poll_id, &poll_info);
+
+            self.env().emit_event(QuorumLowered {
+                poll_id: poll_id.clone(),
+                old_quorum,
+                new_quorum,
+            });
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn close_poll(
+            &mut self,
+            poll_id: String,
+        ) -> Result<(), Error> {
+            let mut poll_info = self.load_poll(&poll_id)?;
+
+            if poll_info.cancelled {
+                return Err(Error::PollCancelled)
+            }
+
+            if poll_info.closed {
+                return Err(Error::PollAlreadyClosed)
+            }
+
+            self.check_finished(&poll_info)?;
+
+            if self.requires_extension(&poll_id, &poll_info) {
+                return Err(Error::TieRequiresExtension)
+            }
+
+            poll_info.closed = true;
+            self.poll_data.insert(&poll_id, &poll_info);
+            self.observe_status(&poll_id, &poll_info);
+
+            let (winning_option, _) = self.winning_option(&poll_id, &poll_info);
+            let total_votes = self.total_votes(&poll_id, &poll_info)?;
+
+            self.env().emit_event(PollClosed {
+                poll_id: poll_id.clone(),
+                winning_option,
+                total_votes,
+            });
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn cancel_poll(
+            &mut self,
+            poll_id: String,
+        ) -> Result<(), Error> {
+            self.ensure_owner_or_creator(&poll_id)?;
+
+            let mut poll_info = self.load_poll(&poll_id)?;
+
+            if poll_info.cancelled {
+                return Err(Error::PollCancelled)
+            }
+
+            // A finished poll has a result, cancelling it would only take the penalty from its voters
+            if matches!(self.status_of(&poll_info), PollStatus::Finished | PollStatus::Closed) {
+                return Err(Error::PollIsFinished)
+            }
+
+            poll_info.cancelled = true;
+            self.poll_data.insert(&poll_id, &poll_info);
+            self.observe_status(&poll_id, &poll_info);
+
+            self.env().emit_event(PollCancelled {
+                poll_id: poll_id.clone(),
+            });
+
+            Ok(())
+        }
+
+        // Refund the listed voters of a cancelled poll, voters with nothing left to withdraw are skipped
+        #[ink(message)]
+        pub fn emergency_refund_all(
+            &mut self,
+            poll_id: String,
+            voters: Vec<AccountId>,
+        ) -> Result<(), Error> {
+            self.ensure_owner()?;
+
+            if voters.len() > MAX_BATCH_SIZE {
+                return Err(Error::BatchTooLarge)
+            }
+
+            let poll_info = self.load_poll(&poll_id)?;
+
+            if !poll_info.cancelled {
+                return Err(Error::PollNotCancelled)
+            }
+
+            for voter in voters {
+                let voting_info = self.voting.get(&(poll_id.clone(), voter)).unwrap_or_default();
+
+                if voting_info.number_of_votes == 0 || voting_info.ceres_withdrawn {
+                    continue
+                }
+
+                self.withdraw_funds(voter, poll_id.clone())?;
+            }
+
+            Ok(())
+        }
+
+        // Metadata can only be fixed before voting opens
+        #[ink(message)]
+        pub fn update_poll_metadata(
+            &mut self,
+            poll_id: String,
+            title: String,
+            description: String,
+        ) -> Result<(), Error> {
+            self.ensure_owner()?;
+
+            let mut poll_info = self.load_poll(&poll_id)?;
+
+            match self.status_of(&poll_info) {
+                PollStatus::Cancelled => return Err(Error::PollCancelled),
+                PollStatus::Active | PollStatus::Finished | PollStatus::Closed => return Err(Error::PollAlreadyStarted),
+                PollStatus::Draft | PollStatus::NotStarted => {}
+            }
+
+            if title.len() > MAX_TITLE_LENGTH || description.len() > MAX_DESCRIPTION_LENGTH {
+                return Err(Error::MetadataTooLong)
+            }
+
+            poll_info.title = title.clone();
+            poll_info.description = description.clone();
+            self.poll_data.insert(&poll_id, &poll_info);
+
+            self.env().emit_event(PollMetadataUpdated {
+                poll_id: poll_id.clone(),
+                title,
+                description,
+            });
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn set_voter_allowed(
+            &mut self,
+            poll_id: String,
+            voter: AccountId,
+            allowed: bool,
+        ) -> Result<(), Error> {
+            self.ensure_owner()?;
+
+            self.load_poll(&poll_id)?;
+
+            self.allowed_voters.insert(&(poll_id, voter), &allowed);
+
+            Ok(())
+        }
+
+        // Ids in creation order, poll_ids is keyed by the creation index
+        #[ink(message)]
+        pub fn get_poll_ids(
+            &self,
+            start: u32,
+            limit: u32,
+        ) -> Vec<String> {
+            let end = start.saturating_add(limit.min(MAX_PAGE_SIZE)).min(self.poll_count);
+
+            (start..end)
+                .filter_map(|index| self.poll_ids.get(index))
+                .collect()
+        }
+
+        // Polls in the order the voter first voted on them
+        #[ink(message)]
+        pub fn get_voter_polls(
+            &self,
+            voter: AccountId,
+            start: u32,
+            limit: u32,
+        ) -> Vec<String> {
+            let end = start
+                .saturating_add(limit.min(MAX_PAGE_SIZE))
+                .min(self.voter_poll_count.get(voter).unwrap_or_default());
+
+            (start..end)
+                .filter_map(|index| self.voter_polls.get((voter, index)))
+                .collect()
+        }
+
+        #[ink(message)]
+        pub fn get_polls_by_category(
+            &self,
+            category: String,
+            start: u32,
+            limit: u32,
+        ) -> Vec<String> {
+            let end = start
+                .saturating_add(limit.min(MAX_PAGE_SIZE))
+                .min(self.category_poll_count.get(&category).unwrap_or_default());
+
+            (start..end)
+                .filter_map(|index| self.category_polls.get((&category, index)))
+                .collect()
+        }
+
+        #[ink(message)]
+        pub fn get_poll_count(&self) -> u32 {
+            self.poll_count
+        }
+
+        // The threshold must be between 1 and the number of admins
+        #[ink(message)]
+        pub fn set_admins(
+            &mut self,
+            admins: Vec<AccountId>,
+            threshold: u8,
+        ) -> Result<(), Error> {
+            self.ensure_owner()?;
+
+            if threshold == 0 || usize::from(threshold) > admins.len() {
+                return Err(Error::InvalidAdmins)
+            }
+
+            if admins.iter().enumerate().any(|(index, admin)| admins[..index].contains(admin)) {
+                return Err(Error::InvalidAdmins)
+            }
+
+            self.admins = admins;
+            self.threshold = threshold;
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_admins(&self) -> Vec<AccountId> {
+            self.admins.clone()
+        }
+
+        #[ink(message)]
+        pub fn get_threshold(&self) -> u8 {
+            self.threshold
+        }
+
+        // The proposer confirms the action, it runs as soon as the threshold is reached
+        #[ink(message)]
+        pub fn propose_admin_action(
+            &mut self,
+            action: AdminAction,
+        ) -> Result<u32, Error> {
+            let caller = self.env().caller();
+
+            if !self.admins.contains(&caller) {
+                return Err(Error::NotAuthorized)
+            }
+
+            let proposal_id = self.next_proposal_id;
+            self.next_proposal_id = proposal_id.checked_add(1).ok_or(Error::ArithmeticOverflow)?;
+
+            self.admin_proposals.insert(proposal_id, &AdminProposal {
+                action: action.clone(),
+                confirmations: Vec::new(),
+            });
+
+            self.env().emit_event(AdminActionProposed {
+                proposal_id,
+                proposer: caller,
+                action,
+            });
+
+            self.confirm_admin_action(proposal_id)?;
+
+            Ok(proposal_id)
+        }
+
+        // A failing action reverts the confirmation, so it can be confirmed again later
+        #[ink(message)]
+        pub fn confirm_admin_action(
+            &mut self,
+            proposal_id: u32,
+        ) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            if !self.admins.contains(&caller) {
+                return Err(Error::NotAuthorized)
+            }
+
+            let mut proposal = self.admin_proposals.get(proposal_id).ok_or(Error::AdminProposalNotFound)?;
+
+            if proposal.confirmations.contains(&caller) {
+                return Err(Error::AlreadyConfirmed)
+            }
+
+            proposal.confirmations.push(caller);
+
+            // Confirmations of removed admins no longer count
+            let confirmations = proposal.confirmations
+                .iter()
+                .filter(|admin| self.admins.contains(admin))
+                .count();
+
+            if confirmations < usize::from(self.threshold) {
+                self.admin_proposals.insert(proposal_id, &proposal);
+                return Ok(())
+            }
+
+            self.admin_proposals.remove(proposal_id);
+
+            // The flag is cleared before any error is returned, it must never outlive the action
+            self.executing = true;
+            let result = self.execute_admin_action(proposal.action.clone());
+            self.executing = false;
+
+            if let Err(error) = result {
+                proposal.confirmations.pop();
+                self.admin_proposals.insert(proposal_id, &proposal);
+                return Err(error)
+            }
+
+            self.env().emit_event(AdminActionExecuted {
+                proposal_id,
+            });
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_admin_proposal(
+            &self,
+            proposal_id: u32,
+        ) -> Option<AdminProposal> {
+            self.admin_proposals.get(proposal_id)
+        }
+
+        // Withdrawals stay available while paused so funds can always be recovered
+        #[ink(message)]
+        pub fn set_paused(
+            &mut self,
+            paused: bool,
+        ) -> Result<(), Error> {
+            self.ensure_owner()?;
+
+            self.paused = paused;
+
+            self.env().emit_event(PausedStateChanged {
+                paused,
+            });
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn is_paused(&self) -> bool {
+            self.paused
+        }
+
+        // Only the surplus above the locked voter funds can be swept
+        #[ink(message)]
+        pub fn sweep_excess(
+            &mut self,
+            token: AccountId,
+            to: AccountId,
+        ) -> Result<Balance, Error> {
+            self.ensure_owner()?;
+
+            let balance = self.balance_of(token, self.env().account_id())?;
+            let amount = balance.saturating_sub(self.total_locked.get(token).unwrap_or_default());
+
+            if amount == 0 {
+                return Ok(0)
+            }
+
+            self.transfer_to(token, to, amount)?;
+
+            self.env().emit_event(ExcessSwept {
+                token,
+                to,
+                amount,
+            });
+
+            Ok(amount)
+        }
+
+        // Bounds that no poll could satisfy are rejected, they would make create_poll always fail
+        #[ink(message)]
+        pub fn set_max_poll_duration(
+            &mut self,
+            max_poll_duration: Timestamp,
+        ) -> Result<(), Error> {
+            self.ensure_owner()?;
+
+            if max_poll_duration == 0 || max_poll_duration < self.min_poll_duration {
+                return Err(Error::InvalidPollDurationBounds)
+            }
+
+            self.max_poll_duration = max_poll_duration;
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_max_poll_duration(&self) -> Timestamp {
+            self.max_poll_duration
+        }
+
+        // The minimum can not exceed the maximum, see set_max_poll_duration
+        #[ink(message)]
+        pub fn set_min_poll_duration(
+            &mut self,
+            min_poll_duration: Timestamp,
+        ) -> Result<(), Error> {
+            self.ensure_owner()?;
+
+            if min_poll_duration > self.max_poll_duration {
+                return Err(Error::InvalidPollDurationBounds)
+            }
+
+            self.min_poll_duration = min_poll_duration;
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_min_poll_duration(&self) -> Timestamp {
+            self.min_poll_duration
+        }
+
+        #[ink(message)]
+        pub fn set_max_options(
+            &mut self,
+            max_options: u32,
+        ) -> Result<(), Error> {
+            self.ensure_owner()?;
+
+            self.max_options = max_options;
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_max_options(&self) -> u32 {
+            self.max_options
+        }
+
+        // CERES a caller must hold to create a poll, 0 disables the check
+        #[ink(message)]
+        pub fn set_min_balance_to_create(
+            &mut self,
+            min_balance_to_create: Balance,
+        ) -> Result<(), Error> {
+            self.ensure_owner()?;
+
+            self.min_balance_to_create = min_balance_to_create;
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_min_balance_to_create(&self) -> Balance {
+            self.min_balance_to_create
+        }
+
+        // None disables the staking boost
+        #[ink(message)]
+        pub fn set_staking_contract(
+            &mut self,
+            staking_contract: Option<AccountId>,
+        ) -> Result<(), Error> {
+            self.ensure_owner()?;
+
+            self.staking_contract = staking_contract;
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_staking_contract(&self) -> Option<AccountId> {
+            self.staking_contract
+        }
+
+        // The multiplier is expressed over BOOST_DENOMINATOR
+        #[ink(message)]
+        pub fn set_boost_multiplier(
+            &mut self,
+            boost_multiplier: u32,
+        ) -> Result<(), Error> {
+            self.ensure_owner()?;
+
+            if boost_multiplier < BOOST_DENOMINATOR {
+                return Err(Error::InvalidBoostMultiplier)
+            }
+
+            self.boost_multiplier = boost_multiplier;
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_boost_multiplier(&self) -> u32 {
+            self.boost_multiplier
+        }
+
+        // The fee is expressed in basis points of the votes, 0 disables it
+        #[ink(message)]
+        pub fn set_fee(
+            &mut self,
+            fee_bps: u16,
+        ) -> Result<(), Error> {
+            self.ensure_owner()?;
+
+            if fee_bps > FEE_DENOMINATOR {
+                return Err(Error::InvalidFee)
+            }
+
+            self.fee_bps = fee_bps;
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_fee(&self) -> u16 {
+            self.fee_bps
+        }
+
+        // Share of refunds from cancelled polls sent to the treasury, in basis points, 0 disables it
+        #[ink(message)]
+        pub fn set_cancel_penalty(
+            &mut self,
+            cancel_penalty_bps: u16,
+        ) -> Result<(), Error> {
+            self.ensure_owner()?;
+
+            if cancel_penalty_bps > MAX_CANCEL_PENALTY_BPS {
+                return Err(Error::InvalidFee)
+            }
+
+            self.cancel_penalty_bps = cancel_penalty_bps;
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_cancel_penalty(&self) -> u16 {
+            self.cancel_penalty_bps
+        }
+
+        #[ink(message)]
+        pub fn set_treasury(
+            &mut self,
+            treasury: AccountId,
+        ) -> Result<(), Error> {
+            self.ensure_owner()?;
+
+            self.treasury = treasury;
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_treasury(&self) -> AccountId {
+            self.treasury
+        }
+
+        // Replace the contract code while keeping its storage.
+        // The new code must keep the CeresGovernance storage layout: existing fields stay in the same
+        // order with the same types, new fields are only appended, and the Lazy and Mapping storage keys
+        // are unchanged. Types stored in mappings (PollInfo, VotingInfo, AdminProposal) must stay decodable.
+        #[ink(message)]
+        pub fn set_code_hash(
+            &mut self,
+            code_hash: Hash,
+        ) -> Result<(), Error> {
+            self.ensure_owner()?;
+
+            self.env().set_code_hash(&code_hash).map_err(|_| Error::CodeUpgradeFailed)?;
+
+            self.env().emit_event(CodeUpgraded {
+                code_hash,
+            });
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn version(&self) -> (u16, u16, u16) {
+            CONTRACT_VERSION
+        }
+
+        #[ink(message)]
+        pub fn contract_info(&self) -> ContractInfo {
+            ContractInfo {
+                admins: self.admins.clone(),
+                threshold: self.threshold,
+                token: self.ceres_token,
+                paused: self.paused,
+                poll_count: self.poll_count,
+            }
+        }
+
+        // Active polls are counted over the most recent MAX_PAGE_SIZE polls only, which keeps the cost bounded
+        #[ink(message)]
+        pub fn get_stats(&self) -> GovernanceStats {
+            let active_polls = (self.poll_count.saturating_sub(MAX_PAGE_SIZE)..self.poll_count)
+                .filter_map(|index| self.poll_ids.get(index))
+                .filter_map(|poll_id| self.poll_data.get(poll_id))
+                .filter(|poll_info| self.status_of(poll_info) == PollStatus::Active)
+                .count() as u32;
+
+            GovernanceStats {
+                poll_count: self.poll_count,
+                active_polls,
+                votes_cast: self.votes_cast,
+                total_locked: self.total_locked.get(self.ceres_token).unwrap_or_default(),
+            }
+        }
+
+        // Owner-gated actions run directly for a single admin, otherwise through a confirmed proposal
+        fn ensure_owner(&self) -> Result<(), Error> {
+            if self.executing {
+                return Ok(())
+            }
+
+            if !self.admins.contains(&self.env().caller()) {
+                return Err(Error::NotAuthorized)
+            }
+
+            if self.threshold > 1 {
+                return Err(Error::InsufficientConfirmations)
+            }
+
+            Ok(())
+        }
+
+        // The poll creator can manage the poll directly, everyone else needs the owner check
+        fn ensure_owner_or_creator(&self, poll_id: &str) -> Result<(), Error> {
+            if self.poll_creators.get(poll_id) == Some(self.env().caller()) {
+                return Ok(())
+            }
+
+            self.ensure_owner().map_err(|error| match error {
+                Error::NotAuthorized => Error::NotPollCreator,
+                error => error,
+            })
+        }
+
+        // Dispatch a confirmed admin action to its owner-gated message
+        fn execute_admin_action(&mut self, action: AdminAction) -> Result<(), Error> {
+            match action {
+                AdminAction::CreatePoll {
+                    poll_id,
+                    number_of_options,
+                    poll_start_timestamp,
+                    poll_end_timestamp,
+                    settings,
+                    option_labels,
+                    title,
+                    description,
+                } => self.create_poll(
+                    poll_id,
+                    number_of_options,
+                    poll_start_timestamp,
+                    poll_end_timestamp,
+                    settings,
+                    option_labels,
+                    title,
+                    description,
+                ),
+                AdminAction::ActivatePoll { poll_id } => self.activate_poll(poll_id),
+                AdminAction::ExtendPoll { poll_id, new_end_timestamp } => self.extend_poll(poll_id, new_end_timestamp),
+                AdminAction::LowerQuorum { poll_id, new_quorum } => self.lower_quorum(poll_id, new_quorum),
+                AdminAction::CancelPoll { poll_id } => self.cancel_poll(poll_id),
+                AdminAction::EmergencyRefundAll { poll_id, voters } => self.emergency_refund_all(poll_id, voters),
+                AdminAction::UpdatePollMetadata { poll_id, title, description } => {
+                    self.update_poll_metadata(poll_id, title, description)
+                }
+                AdminAction::SetVoterAllowed { poll_id, voter, allowed } => self.set_voter_allowed(poll_id, voter, allowed),
+                AdminAction::SetAdmins { admins, threshold } => self.set_admins(admins, threshold),
+                AdminAction::SetPaused(paused) => self.set_paused(paused),
+                AdminAction::SweepExcess { token, to } => self.sweep_excess(token, to).map(|_| ()),
+                AdminAction::SetMaxPollDuration(max_poll_duration) => self.set_max_poll_duration(max_poll_duration),
+                AdminAction::SetMinPollDuration(min_poll_duration) => self.set_min_poll_duration(min_poll_duration),
+                AdminAction::SetMaxOptions(max_options) => self.set_max_options(max_options),
+                AdminAction::SetMinBalanceToCreate(min_balance_to_create) => self.set_min_balance_to_create(min_balance_to_create),
+                AdminAction::SetStakingContract(staking_contract) => self.set_staking_contract(staking_contract),
+                AdminAction::SetBoostMultiplier(boost_multiplier) => self.set_boost_multiplier(boost_multiplier),
+                AdminAction::SetFee(fee_bps) => self.set_fee(fee_bps),
+                AdminAction::SetCancelPenalty(cancel_penalty_bps) => self.set_cancel_penalty(cancel_penalty_bps),
+                AdminAction::SetTreasury(treasury) => self.set_treasury(treasury),
+                AdminAction::SetCodeHash(code_hash) => self.set_code_hash(code_hash),
+                AdminAction::CreatePollAuto {
+                    number_of_options,
+                    poll_start_timestamp,
+                    poll_end_timestamp,
+                    settings,
+                    option_labels,
+                    title,
+                    description,
+                } => self.create_poll_auto(
+                    number_of_options,
+                    poll_start_timestamp,
+                    poll_end_timestamp,
+                    settings,
+                    option_labels,
+                    title,
+                    description,
+                ).map(|_| ()),
+            }
+        }
+
+        // Body of vote_weighted, run under the reentrancy lock
+        fn apply_weighted_vote(
+            &mut self,
+            caller: AccountId,
+            poll_id: String,
+            allocations: Vec<(u32, Balance)>,
+        ) -> Result<(), Error> {
+            if self.paused {
+                return Err(Error::ContractPaused)
+            }
+
+            if allocations.is_empty() {
+                return Err(Error::InvalidNumberOfVotes)
+            }
+
+            let poll_info = self.load_poll(&poll_id)?;
+
+            self.check_active(&poll_info)?;
+
+            if poll_info.settings.voting_mode != VotingMode::MultiChoice || poll_info.settings.reveal_end_timestamp != 0 {
+                return Err(Error::VoteDenied)
+            }
+
+            if !self.is_voter_allowed(&poll_id, &poll_info, caller) {
+                return Err(Error::VoterNotAllowed)
+            }
+
+            let mut total_supplied: Balance = 0;
+            let mut total_fee: Balance = 0;
+            let mut weights = Vec::with_capacity(allocations.len());
+
+            for (index, (option, number_of_votes)) in allocations.iter().enumerate() {
+                if !Self::is_valid_option(&poll_info, *option) {
+                    return Err(Error::InvalidNumberOfOption)
+                }
+
+                if allocations[..index].iter().any(|(previous, _)| previous == option) {
+                    return Err(Error::InvalidNumberOfOption)
+                }
+
+                if *number_of_votes == 0 {
+                    return Err(Error::InvalidNumberOfVotes)
+                }
+
+                if poll_info.settings.vote_step != 0 && number_of_votes % poll_info.settings.vote_step != 0 {
+                    return Err(Error::InvalidVoteStep)
+                }
+
+                // The protocol fee is taken from every allocation, only the rest is escrowed and tallied
+                let fee = self.fee_of(&poll_info, *number_of_votes)?;
+                total_fee = total_fee.checked_add(fee).ok_or(Error::ArithmeticOverflow)?;
+                total_supplied = total_supplied.checked_add(*number_of_votes).ok_or(Error::ArithmeticOverflow)?;
+                weights.push((*option, number_of_votes - fee));
+            }
+
+            if total_supplied < poll_info.settings.min_votes {
+                return Err(Error::InvalidNumberOfVotes)
+            }
+
+            let total_votes = total_supplied - total_fee;
+
+            if total_votes == 0 {
+                return Err(Error::InvalidNumberOfVotes)
+            }
+
+            self.check_vote_cap(&poll_id, &poll_info, total_votes)?;
+
+            let mut voting_info = self.voting.get(&(poll_id.clone(), caller)).unwrap_or_default();
+            let first_vote = !voting_info.has_voted;
+
+            if voting_info.ceres_withdrawn {
+                return Err(Error::AlreadyWithdrawn)
+            }
+
+            voting_info.has_voted = true;
+
+            voting_info.number_of_votes = voting_info.number_of_votes
+                .checked_add(total_votes)
+                .ok_or(Error::ArithmeticOverflow)?;
+            voting_info.tally_votes = voting_info.number_of_votes;
+
+            if poll_info.settings.max_votes_per_voter != 0 && voting_info.number_of_votes > poll_info.settings.max_votes_per_voter {
+                return Err(Error::MaxVotesExceeded)
+            }
+
+            if voting_info.number_of_votes > self.balance_of_at(self.token_of(&poll_info), caller, poll_info.snapshot_block)? {
+                return Err(Error::NotEnoughFunds)
+            }
+
+            let received_amount = self.transfer_from_voter(self.token_of(&poll_info), caller, total_votes)?;
+            self.collect_fee(caller, &poll_id, &poll_info, total_fee)?;
+            voting_info.locked_amount = voting_info.locked_amount.checked_add(received_amount).ok_or(Error::ArithmeticOverflow)?;
+
+            // Fee-on-transfer tokens may deliver less than the allocations, every allocation shrinks to its share of what arrived
+            let mut counted_votes = total_votes;
+
+            if received_amount < total_votes {
+                for (_, number_of_votes) in weights.iter_mut() {
+                    *number_of_votes = number_of_votes
+                        .checked_mul(received_amount)
+                        .map(|votes| votes / total_votes)
+                        .ok_or(Error::ArithmeticOverflow)?;
+                }
+
+                counted_votes = weights.iter().map(|(_, number_of_votes)| number_of_votes).sum();
+
+                if counted_votes == 0 {
+                    return Err(Error::InvalidNumberOfVotes)
+                }
+
+                voting_info.number_of_votes = voting_info.number_of_votes - total_votes + counted_votes;
+                voting_info.tally_votes = voting_info.number_of_votes;
+            }
+
+            let locked_balance = self.locked_balances.get((caller, self.token_of(&poll_info))).unwrap_or_default();
+            self.locked_balances.insert((caller, self.token_of(&poll_info)), &locked_balance.checked_add(received_amount).ok_or(Error::ArithmeticOverflow)?);
+
+            let total_locked = self.total_locked.get(self.token_of(&poll_info)).unwrap_or_default();
+            self.total_locked.insert(self.token_of(&poll_info), &total_locked.checked_add(received_amount).ok_or(Error::ArithmeticOverflow)?);
+
+            self.voting.insert(&(poll_id.clone(), caller), &voting_info);
+            self.votes_cast = self.votes_cast.checked_add(counted_votes).ok_or(Error::ArithmeticOverflow)?;
+
+            for (option, number_of_votes) in weights {
+                let option_votes = self.option_votes.get(&(poll_id.clone(), option)).unwrap_or_default();
+                self.option_votes.insert(&(poll_id.clone(), option), &option_votes.checked_add(number_of_votes).ok_or(Error::ArithmeticOverflow)?);
+
+                self.env().emit_event(Voted {
+                    poll_id: poll_id.clone(),
+                    voter: caller,
+                    voting_option: option,
+                    number_of_votes,
+                    cumulative_votes: voting_info.number_of_votes,
+                    received_amount,
+                    timestamp: self.env().block_timestamp(),
+                });
+            }
+
+            if first_vote {
+                let voter_count = self.voter_count.get(&poll_id).unwrap_or_default();
+                self.voter_count.insert(&poll_id, &voter_count.checked_add(1).ok_or(Error::ArithmeticOverflow)?);
+                self.record_voter_poll(caller, &poll_id)?;
+            }
+
+            self.observe_status(&poll_id, &poll_info);
+
+            Ok(())
+        }
+
+        // Body of withdraw_partial, run under the reentrancy lock
+        fn release_partial(
+            &mut self,
+            voter: AccountId,
+            poll_id: String,
+            amount: Balance,
+        ) -> Result<(), Error> {
+            let (poll_info, voting_info) = self.check_withdrawal(voter, &poll_id)?;
+
+            if amount == 0 {
+                return Err(Error::InvalidNumberOfVotes)
+            }
+
+            if amount > voting_info.locked_amount {
+                return Err(Error::NotEnoughFunds)
+            }
+
+            self.release(voter, &poll_id, &poll_info, voting_info, amount)?;
+
+            Ok(())
+        }
+
+        // Record a vote for the voter, locking the CERES tokens from the voter's account and returning the updated voter state
+        fn cast_vote(
+            &mut self,
+            voter: AccountId,
+            poll_id: String,
+            voting_option: u32,
+            number_of_votes: Balance,
+            revealed: bool,
+        ) -> Result<VotingInfo, Error> {
+            self.guarded(|this| this.apply_vote(voter, poll_id, voting_option, number_of_votes, revealed))
+        }
+
+        // Body of cast_vote, run under the reentrancy lock
+        fn apply_vote(
+            &mut self,
+            voter: AccountId,
+            poll_id: String,
+            voting_option: u32,
+            number_of_votes: Balance,
+            revealed: bool,
+        ) -> Result<VotingInfo, Error> {
+            let mut update = self.prepare_vote(voter, &poll_id, voting_option, number_of_votes, revealed)?;
+            let token = self.token_of(&update.poll_info);
+
+            // Every check has passed, storage is only written from here on
+            let mut received_amount = 0;
+
+            if update.amount > 0 {
+                received_amount = self.transfer_from_voter(token, voter, update.amount)?;
+
+                // Fee-on-transfer tokens deliver less than was pulled, only what the contract holds is counted
+                if received_amount < update.amount {
+                    self.recount_vote(voter, &mut update, received_amount)?;
+                }
+            }
+
+            // Charged while the lock is held, on every path that casts a vote
+            self.collect_fee(voter, &poll_id, &update.poll_info, update.fee)?;
+
+            self.locked_balances.insert((voter, token), &update.locked_balance);
+            self.total_locked.insert(token, &update.total_locked);
+            self.voting.insert(&(poll_id.clone(), voter), &update.voting_info);
+            self.option_votes.insert(&(poll_id.clone(), voting_option), &update.option_votes);
+            self.voter_count.insert(&poll_id, &update.voter_count);
+            self.votes_cast = self.votes_cast.checked_add(update.number_of_votes).ok_or(Error::ArithmeticOverflow)?;
+
+            if update.first_vote {
+                self.record_voter_poll(voter, &poll_id)?;
+            }
+
+            self.observe_status(&poll_id, &update.poll_info);
+
+            self.env().emit_event(Voted {
+                poll_id: poll_id.clone(),
+                voter,
+                voting_option,
+                number_of_votes: update.number_of_votes,
+                cumulative_votes: update.voting_info.number_of_votes,
+                received_amount,
+                timestamp: self.env().block_timestamp(),
+            });
+
+            if update.boosted_votes != update.number_of_votes {
+                self.env().emit_event(BoostApplied {
+                    poll_id: poll_id.clone(),
+                    voter,
+                    number_of_votes: update.number_of_votes,
+                    boosted_votes: update.boosted_votes,
+                });
+            }
+
+            Ok(update.voting_info)
+        }
+
+        // Shrink a prepared vote to the weight the received amount pays for, the vote was already validated at its full size
+        fn recount_vote(
+            &self,
+            voter: AccountId,
+            update: &mut VoteUpdate,
+            received_amount: Balance,
+        ) -> Result<(), Error> {
+            let shortfall = update.amount - received_amount;
+            let previous_votes = update.voting_info.number_of_votes - update.number_of_votes;
+            let previous_tally_votes = update.voting_info.tally_votes - update.tally_votes;
+            let locked_amount = update.voting_info.locked_amount - shortfall;
+
+            // The full cumulative vote costs more than is locked now, so the largest affordable vote lies below it
+            let cumulative_votes = match update.poll_info.settings.voting_mode {
+                VotingMode::Quadratic => {
+                    let (mut low, mut high) = (previous_votes, update.voting_info.number_of_votes);
+
+                    while high - low > 1 {
+                        let middle = low + (high - low) / 2;
+
+                        if Self::quadratic_cost(&update.poll_info, middle)? <= locked_amount {
+                            low = middle;
+                        } else {
+                            high = middle;
+                        }
+                    }
+
+                    low
+                }
+                _ => previous_votes.checked_add(received_amount).ok_or(Error::ArithmeticOverflow)?,
+            };
+            let number_of_votes = cumulative_votes - previous_votes;
+
+            if number_of_votes == 0 {
+                return Err(Error::InvalidNumberOfVotes)
+            }
+
+            let boosted_votes = self.boosted(voter, number_of_votes)?;
+            let tally_votes = match update.poll_info.settings.voting_mode {
+                VotingMode::TimeDecay => self.decayed(&update.poll_info, boosted_votes)?,
+                _ => boosted_votes,
+            };
+
+            update.option_votes = update.option_votes - update.tally_votes + tally_votes;
+            update.voting_info.number_of_votes = cumulative_votes;
+            update.voting_info.locked_amount = locked_amount;
+            update.voting_info.tally_votes = previous_tally_votes + tally_votes;
+            update.number_of_votes = number_of_votes;
+            update.amount = received_amount;
+            update.boosted_votes = boosted_votes;
+            update.tally_votes = tally_votes;
+            update.locked_balance -= shortfall;
+            update.total_locked -= shortfall;
+
+            Ok(())
+        }
+
+        // Validate a vote and compute the state it would produce, without writing anything
+        fn prepare_vote(
+            &self,
+            voter: AccountId,
+            poll_id: &str,
+            voting_option: u32,
+            number_of_votes: Balance,
+            revealed: bool,
+        ) -> Result<VoteUpdate, Error> {
+            if self.paused {
+                return Err(Error::ContractPaused)
+            }
+
+            let poll_info = self.load_poll(poll_id)?;
+
+            let number_of_votes = match poll_info.settings.voting_mode {
+                VotingMode::OnePerAccount => 1,
+                _ => number_of_votes,
+            };
+
+            // The protocol fee is taken from the votes, only the rest is escrowed as voting weight,
+            // while min_votes and vote_step apply to the votes the caller supplied
+            let supplied_votes = number_of_votes;
+            let fee = self.fee_of(&poll_info, supplied_votes)?;
+            let number_of_votes = supplied_votes - fee;
+
+            if number_of_votes == 0 {
+                return Err(Error::InvalidNumberOfVotes)
+            }
+
+            // Revealed votes are counted after the poll end, their window is checked by reveal_vote
+            if revealed {
+                if poll_info.cancelled {
+                    return Err(Error::PollCancelled)
+                }
+            } else {
+                self.check_active(&poll_info)?;
+
+                if poll_info.settings.reveal_end_timestamp != 0 {
+                    return Err(Error::VoteDenied)
+                }
+            }
+
+            if poll_info.settings.voting_mode == VotingMode::MultiChoice {
+                return Err(Error::VoteDenied)
+            }
+
+            if !self.is_voter_allowed(poll_id, &poll_info, voter) {
+                return Err(Error::VoterNotAllowed)
+            }
+
+            if supplied_votes < poll_info.settings.min_votes {
+                return Err(Error::InvalidNumberOfVotes)
+            }
+
+            if poll_info.settings.vote_step != 0 && supplied_votes % poll_info.settings.vote_step != 0 {
+                return Err(Error::InvalidVoteStep)
+            }
+    
+            if !Self::is_valid_option(&poll_info, voting_option) {
+                return Err(Error::InvalidNumberOfOption)
+            }
+
+            let mut voting_info = self.voting.get((poll_id, voter)).unwrap_or_default();
+            let first_vote = !voting_info.has_voted;
+
+            if voting_info.ceres_withdrawn {
+                return Err(Error::AlreadyWithdrawn)
+            }
+
+            if !first_vote && voting_info.voting_option != voting_option {
+                return Err(Error::VoteDenied)
+            }
+
+            if !first_vote && poll_info.settings.voting_mode == VotingMode::OnePerAccount {
+                return Err(Error::VoteDenied)
+            }
+
+            let cumulative_votes = voting_info.number_of_votes
+                .checked_add(number_of_votes)
+                .ok_or(Error::ArithmeticOverflow)?;
+
+            if poll_info.settings.max_votes_per_voter != 0 && cumulative_votes > poll_info.settings.max_votes_per_voter {
+                return Err(Error::MaxVotesExceeded)
+            }
+
+            // Tallies count raw votes while the escrow holds their CERES cost
+            let locked_amount = match poll_info.settings.voting_mode {
+                VotingMode::Linear | VotingMode::MultiChoice | VotingMode::TimeDecay => cumulative_votes,
+                VotingMode::Quadratic => Self::quadratic_cost(&poll_info, cumulative_votes)?,
+                VotingMode::OnePerAccount => 0,
+            };
+
+            // Voting power is capped by the CERES balance at the poll snapshot
+            if locked_amount > 0 && locked_amount > self.balance_of_at(self.token_of(&poll_info), voter, poll_info.snapshot_block)? {
+                return Err(Error::NotEnoughFunds)
+            }
+
+            let amount = locked_amount.checked_sub(voting_info.locked_amount).ok_or(Error::ArithmeticOverflow)?;
+
+            // Stakers get boosted tally votes while only the raw votes are escrowed, accounts are never boosted
+            let boosted_votes = match poll_info.settings.voting_mode {
+                VotingMode::OnePerAccount => number_of_votes,
+                _ => self.boosted(voter, number_of_votes)?,
+            };
+            let tally_votes = match poll_info.settings.voting_mode {
+                VotingMode::TimeDecay => self.decayed(&poll_info, boosted_votes)?,
+                _ => boosted_votes,
+            };
+            let cumulative_tally_votes = voting_info.tally_votes
+                .checked_add(tally_votes)
+                .ok_or(Error::ArithmeticOverflow)?;
+
+            self.check_vote_cap(poll_id, &poll_info, tally_votes)?;
+
+            let locked_balance = self.locked_balances.get((voter, self.token_of(&poll_info))).unwrap_or_default()
+                .checked_add(amount)
+                .ok_or(Error::ArithmeticOverflow)?;
+            let total_locked = self.total_locked.get(self.token_of(&poll_info)).unwrap_or_default()
+                .checked_add(amount)
+                .ok_or(Error::ArithmeticOverflow)?;
+            let option_votes = self.option_votes.get((poll_id, voting_option)).unwrap_or_default()
+                .checked_add(tally_votes)
+                .ok_or(Error::ArithmeticOverflow)?;
+            let voter_count = self.voter_count.get(poll_id).unwrap_or_default()
+                .checked_add(u32::from(first_vote))
+                .ok_or(Error::ArithmeticOverflow)?;
+
+            voting_info.voting_option = voting_option;
+            voting_info.has_voted = true;
+            voting_info.number_of_votes = cumulative_votes;
+            voting_info.locked_amount = locked_amount;
+            voting_info.tally_votes = cumulative_tally_votes;
+
+            Ok(VoteUpdate {
+                poll_info,
+                voting_info,
+                number_of_votes,
+                first_vote,
+                amount,
+                fee,
+                boosted_votes,
+                tally_votes,
+                locked_balance,
+                total_locked,
+                option_votes,
+                voter_count,
+            })
+        }
+
+        // Return the locked CERES of the voter, yielding the refunded amount and whether the poll reached quorum
+        fn withdraw_funds(
+            &mut self,
+            voter: AccountId,
+            poll_id: String,
+        ) -> Result<(Balance, bool), Error> {
+            self.guarded(|this| this.release_funds(voter, poll_id))
+        }
+
+        // Body of withdraw_funds, run under the reentrancy lock
+        fn release_funds(
+            &mut self,
+            voter: AccountId,
+            poll_id: String,
+        ) -> Result<(Balance, bool), Error> {
+            let (poll_info, voting_info) = self.check_withdrawal(voter, &poll_id)?;
+            let amount = voting_info.locked_amount;
+
+            self.release(voter, &poll_id, &poll_info, voting_info, amount)
+        }
+
+        // Refund the given amount of the voter's escrow, yielding the refunded amount and whether the poll reached quorum.
+        // The escrow is settled before the transfer so a reentrant or repeated call finds nothing left,
+        // a failed transfer writes it back because the error alone does not undo storage writes everywhere
+        fn release(
+            &mut self,
+            voter: AccountId,
+            poll_id: &str,
+            poll_info: &PollInfo,
+            mut voting_info: VotingInfo,
+            amount: Balance,
+        ) -> Result<(Balance, bool), Error> {
+            let token = self.token_of(poll_info);
+            let locked_balance = self.locked_balances.get((voter, token)).unwrap_or_default();
+            let total_locked = self.total_locked.get(token).unwrap_or_default();
+
+            voting_info.locked_amount = voting_info.locked_amount.checked_sub(amount).ok_or(Error::ArithmeticOverflow)?;
+            voting_info.ceres_withdrawn = voting_info.locked_amount == 0;
+            self.voting.insert((poll_id, voter), &voting_info);
+            self.locked_balances.insert((voter, token), &locked_balance.checked_sub(amount).ok_or(Error::ArithmeticOverflow)?);
+            self.total_locked.insert(token, &total_locked.checked_sub(amount).ok_or(Error::ArithmeticOverflow)?);
+
+            // One vote per account polls lock nothing
+            let refunded = if amount > 0 {
+                match self.refund(poll_id, poll_info, voter, amount) {
+                    Ok(refunded) => refunded,
+                    Err(error) => {
+                        voting_info.locked_amount += amount;
+                        voting_info.ceres_withdrawn = false;
+                        self.voting.insert((poll_id, voter), &voting_info);
+                        self.locked_balances.insert((voter, token), &locked_balance);
+                        self.total_locked.insert(token, &total_locked);
+                        return Err(error)
+                    }
+                }
+            } else {
+                0
+            };
+
+            // A tally too large to sum is above any quorum, and it must never block a refund
+            let quorum_reached = self.quorum_reached(poll_id, poll_info).unwrap_or(true);
+
+            self.observe_status(poll_id, poll_info);
+
+            self.env().emit_event(FundsWithdrawn {
+                poll_id: poll_id.into(),
+                voter,
+                amount: refunded,
+                quorum_reached,
+                timestamp: self.env().block_timestamp(),
+            });
+
+            Ok((refunded, quorum_reached))
+        }
+
+        // Poll and voting info of a voter that is allowed to withdraw
+        fn check_withdrawal(
+            &self,
+            voter: AccountId,
+            poll_id: &str,
+        ) -> Result<(PollInfo, VotingInfo), Error> {
+            let poll_info = self.load_poll(poll_id)?;
+
+            // Voters of a cancelled poll can withdraw immediately
+            if !poll_info.cancelled {
+                self.check_finished(&poll_info)?;
+
+                let poll_end_timestamp = poll_info.poll_end_timestamp.max(poll_info.settings.reveal_end_timestamp);
+
+                if self.now(poll_info.settings.window) < poll_end_timestamp.saturating_add(poll_info.settings.withdrawal_delay) {
+                    return Err(Error::WithdrawalNotYetAvailable)
+                }
+            }
+
+            let voting_info = self.voting.get((poll_id, voter)).unwrap_or_default();
+
+            if voting_info.number_of_votes == 0 {
+                return Err(Error::InvalidVotes)
+            }
+
+            if voting_info.ceres_withdrawn {
+                return Err(Error::FundsAlreadyWithdrawn)
+            }
+
+            Ok((poll_info, voting_info))
+        }
+
+        // Hex encoded Blake2x256 hash of (caller, poll_count, block_number)
+        fn generate_poll_id(&self) -> String {
+            const HEX: &[u8; 16] = b"0123456789abcdef";
+
+            let mut hash = <Blake2x256 as ink::env::hash::HashOutput>::Type::default();
+            ink::env::hash_encoded::<Blake2x256, _>(
+                &(self.env().caller(), self.poll_count, self.env().block_number()),
+                &mut hash,
+            );
+
+            hash.iter()
+                .flat_map(|byte| [HEX[(byte >> 4) as usize] as char, HEX[(byte & 0x0f) as usize] as char])
+                .collect()
+        }
+
+        // Remember the poll on the voter's first vote in it, each poll is stored under its own index
+        fn record_voter_poll(&mut self, voter: AccountId, poll_id: &str) -> Result<(), Error> {
+            let index = self.voter_poll_count.get(voter).unwrap_or_default();
+
+            self.voter_polls.insert((voter, index), &String::from(poll_id));
+            self.voter_poll_count.insert(voter, &index.checked_add(1).ok_or(Error::ArithmeticOverflow)?);
+
+            Ok(())
+        }
+
+        // Remember the poll under its category, each poll is stored under its own index
+        fn record_category_poll(&mut self, category: &str, poll_id: &str) -> Result<(), Error> {
+            let index = self.category_poll_count.get(category).unwrap_or_default();
+
+            self.category_polls.insert((category, index), &String::from(poll_id));
+            self.category_poll_count.insert(category, &index.checked_add(1).ok_or(Error::ArithmeticOverflow)?);
+
+            Ok(())
+        }
+
+        // Unrestricted polls are open to everyone
+        fn is_voter_allowed(&self, poll_id: &str, poll_info: &PollInfo, voter: AccountId) -> bool {
+            !poll_info.settings.restricted || self.allowed_voters.get((poll_id, voter)).unwrap_or_default()
+        }
+
+        // Run the body of a message that calls the token contract under the reentrancy lock.
+        // The lock is released on every exit path, so a failed body never leaves it held even where errors do not revert.
+        fn guarded<T>(&mut self, body: impl FnOnce(&mut Self) -> Result<T, Error>) -> Result<T, Error> {
+            self.lock()?;
+            let result = body(self);
+            self.unlock();
+
+            result
+        }
+
+        // Take the reentrancy lock, failing when a call further up the stack already holds it
+        fn lock(&mut self) -> Result<(), Error> {
+            if self.locked.get().unwrap_or_default() {
+                return Err(Error::ReentrancyDetected)
+            }
+
+            self.locked.set(&true);
+
+            Ok(())
+        }
+
+        fn unlock(&mut self) {
+            self.locked.set(&false);
+        }
+
+        // Stored poll, or PollDoesNotExist
+        fn load_poll(&self, poll_id: &str) -> Result<PollInfo, Error> {
+            self.poll_data.get(poll_id).ok_or(Error::PollDoesNotExist)
+        }
+
+        // Voting on the poll is open right now
+        fn check_active(&self, poll_info: &PollInfo) -> Result<(), Error> {
+            match self.status_of(poll_info) {
+                PollStatus::Cancelled => Err(Error::PollCancelled),
+                PollStatus::Draft => Err(Error::PollIsDraft),
+                PollStatus::NotStarted => Err(Error::PollIsNotStarted),
+                PollStatus::Finished | PollStatus::Closed => Err(Error::PollIsFinished),
+                PollStatus::Active => {
+                    if self.now(poll_info.settings.window) < poll_info.poll_start_timestamp.saturating_add(poll_info.settings.start_buffer) {
+                        return Err(Error::PollIsNotStarted)
+                    }
+
+                    Ok(())
+                }
+            }
+        }
+
+        // Voting and any reveal window are over, a cancelled poll never finishes
+        fn check_finished(&self, poll_info: &PollInfo) -> Result<(), Error> {
+            match self.status_of(poll_info) {
+                PollStatus::Cancelled => Err(Error::PollCancelled),
+                PollStatus::Finished | PollStatus::Closed if self.now(poll_info.settings.window) > poll_info.settings.reveal_end_timestamp => Ok(()),
+                _ => Err(Error::PollIsNotFinished),
+            }
+        }
+
+        // The tally with the new tally votes added must stay within the poll's total vote cap
+        fn check_vote_cap(&self, poll_id: &str, poll_info: &PollInfo, tally_votes: Balance) -> Result<(), Error> {
+            if poll_info.settings.max_total_votes == 0 {
+                return Ok(())
+            }
+
+            let total_votes = self.total_votes(poll_id, poll_info)?
+                .checked_add(tally_votes)
+                .ok_or(Error::ArithmeticOverflow)?;
+
+            if total_votes > poll_info.settings.max_total_votes {
+                return Err(Error::PollVoteCapReached)
+            }
+
+            Ok(())
+        }
+
+        // Current time in the unit of the poll window
+        fn now(&self, window: PollWindow) -> Timestamp {
+            match window {
+                PollWindow::Timestamp => self.env().block_timestamp(),
+                PollWindow::BlockNumber => Timestamp::from(self.env().block_number()),
+            }
+        }
+
+        // Minimum and maximum poll duration in the unit of the window, block number windows convert them at the expected block time
+        fn duration_bounds(&self, window: PollWindow) -> (Timestamp, Timestamp) {
+            match window {
+                PollWindow::Timestamp => (self.min_poll_duration, self.max_poll_duration),
+                PollWindow::BlockNumber => (
+                    self.min_poll_duration.div_ceil(EXPECTED_BLOCK_TIME),
+                    self.max_poll_duration / EXPECTED_BLOCK_TIME,
+                ),
+            }
+        }
+
+        // Voting is open from the start to the end timestamp inclusive, a cancelled poll is never open
+        fn status_of(&self, poll_info: &PollInfo) -> PollStatus {
+            let current_timestamp = self.now(poll_info.settings.window);
+
+            if poll_info.cancelled {
+                PollStatus::Cancelled
+            } else if poll_info.settings.draft {
+                PollStatus::Draft
+            } else if poll_info.closed {
+                PollStatus::Closed
+            } else if current_timestamp < poll_info.poll_start_timestamp {
+                PollStatus::NotStarted
+            } else if current_timestamp > poll_info.poll_end_timestamp {
+                PollStatus::Finished
+            } else {
+                PollStatus::Active
+            }
+        }
+
+        // Emit PollStateChanged when the status differs from the last one observed, transitions are time based so they surface lazily
+        fn observe_status(&mut self, poll_id: &str, poll_info: &PollInfo) {
+            let new_status = self.status_of(poll_info);
+            let old_status = self.poll_statuses.get(poll_id).unwrap_or(PollStatus::NotStarted);
+
+            if old_status != new_status {
+                self.poll_statuses.insert(poll_id, &new_status);
+
+                self.env().emit_event(PollStateChanged {
+                    poll_id: poll_id.into(),
+                    old_status,
+                    new_status,
+                });
+            }
+        }
+
+        // Option with the most votes, on a tie the lowest option wins
+        fn winning_option(&self, poll_id: &str, poll_info: &PollInfo) -> (u32, Balance) {
+            let mut winning_option = 1;
+            let mut winning_votes = self.option_votes.get((poll_id, 1)).unwrap_or_default();
+
+            for option in 2..=poll_info.number_of_options {
+                let option_votes = self.option_votes.get((poll_id, option)).unwrap_or_default();
+                if option_votes > winning_votes {
+                    winning_option = option;
+                    winning_votes = option_votes;
+                }
+            }
+
+            (winning_option, winning_votes)
+        }
+
+        // More than one option holds the leading vote count
+        fn is_tied(&self, poll_id: &str, poll_info: &PollInfo, winning_votes: Balance) -> bool {
+            // Options without votes are not tied, there is just no leader
+            if winning_votes == 0 {
+                return false
+            }
+
+            (1..=poll_info.number_of_options)
+                .filter(|option| self.option_votes.get((poll_id, *option)).unwrap_or_default() == winning_votes)
+                .count() > 1
+        }
+
+        // Poll is tied and its tie-break asks for an extension
+        fn requires_extension(&self, poll_id: &str, poll_info: &PollInfo) -> bool {
+            let (_, winning_votes) = self.winning_option(poll_id, poll_info);
+
+            poll_info.settings.tie_break == TieBreak::RequireExtension && self.is_tied(poll_id, poll_info, winning_votes)
+        }
+
+        // Both the token quorum and the unique voter quorum are met
+        fn quorum_reached(&self, poll_id: &str, poll_info: &PollInfo) -> Result<bool, Error> {
+            Ok(self.total_votes(poll_id, poll_info)? >= poll_info.settings.quorum
+                && self.voter_count.get(poll_id).unwrap_or_default() >= poll_info.settings.min_unique_voters)
+        }
+
+        // Sum of votes across all options of a poll, abstain votes included
+        fn total_votes(&self, poll_id: &str, poll_info: &PollInfo) -> Result<Balance, Error> {
+            let first_option = if poll_info.settings.allow_abstain { 0 } else { 1 };
+
+            (first_option..=poll_info.number_of_options)
+                .map(|option| self.option_votes.get((poll_id, option)).unwrap_or_default())
+                .try_fold(0, |total: Balance, option_votes| total.checked_add(option_votes))
+                .ok_or(Error::ArithmeticOverflow)
+        }
+
+        // Options are numbered from 1 to number_of_options inclusive, the abstain tally is kept under option 0
+        fn is_valid_option(poll_info: &PollInfo, option: u32) -> bool {
+            (1..=poll_info.number_of_options).contains(&option) || (poll_info.settings.allow_abstain && option == 0)
+        }
+
+        // Token locked by the poll
+        fn token_of(&self, poll_info: &PollInfo) -> AccountId {
+            poll_info.settings.token.unwrap_or(self.ceres_token)
+        }
+
+        // Historical token balance of the owner at the given block.
+        // The token must expose `PSP22Snapshot::balance_of_at(owner: AccountId, block: BlockNumber) -> Balance`.
+        #[cfg(not(test))]
+        fn balance_of_at(&self, token: AccountId, owner: AccountId, block: BlockNumber) -> Result<Balance, Error> {
+            let result = build_call::<DefaultEnvironment>()
+                .call(token)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(ink::selector_bytes!("PSP22Snapshot::balance_of_at")))
+                        .push_arg(owner)
+                        .push_arg(block),
+                )
+                .returns::<Balance>()
+                .try_invoke();
+
+            match result {
+                Ok(Ok(balance)) => Ok(balance),
+                _ => Err(Error::TokenQueryFailed),
+            }
+        }
+
+        // Part of the votes taken as protocol fee, the rest is escrowed as voting weight
+        fn fee_of(&self, poll_info: &PollInfo, number_of_votes: Balance) -> Result<Balance, Error> {
+            // Nothing is escrowed in a one vote per account poll, so there is nothing to take a fee from
+            if poll_info.settings.voting_mode == VotingMode::OnePerAccount {
+                return Ok(0)
+            }
+
+            number_of_votes
+                .checked_mul(Balance::from(self.fee_bps))
+                .map(|fee| fee / Balance::from(FEE_DENOMINATOR))
+                .ok_or(Error::ArithmeticOverflow)
+        }
+
+        // Transfer the fee from the voter to the treasury in the poll token
+        fn collect_fee(&mut self, voter: AccountId, poll_id: &str, poll_info: &PollInfo, fee: Balance) -> Result<(), Error> {
+            if fee == 0 {
+                return Ok(())
+            }
+
+            self.transfer_from_to(self.token_of(poll_info), voter, self.treasury, fee)?;
+
+            self.env().emit_event(FeeCollected {
+                poll_id: poll_id.into(),
+                voter,
+                treasury: self.treasury,
+                amount: fee,
+            });
+
+            Ok(())
+        }
+
+        // Penalty taken from a refund of a cancelled poll
+        fn cancel_penalty_of(&self, poll_info: &PollInfo, amount: Balance) -> Result<Balance, Error> {
+            if !poll_info.cancelled {
+                return Ok(0)
+            }
+
+            amount
+                .checked_mul(Balance::from(self.cancel_penalty_bps))
+                .map(|penalty| penalty / Balance::from(FEE_DENOMINATOR))
+                .ok_or(Error::ArithmeticOverflow)
+        }
+
+        // Release locked tokens to the voter, then send any cancel penalty to the treasury, returning what the voter received.
+        // Paying the voter first means an error leaves every token in the contract, a penalty the treasury refuses stays as excess for sweep_excess
+        fn refund(&self, poll_id: &str, poll_info: &PollInfo, voter: AccountId, amount: Balance) -> Result<Balance, Error> {
+            let penalty = self.cancel_penalty_of(poll_info, amount)?;
+            let refunded = amount.checked_sub(penalty).ok_or(Error::ArithmeticOverflow)?;
+
+            if refunded > 0 {
+                self.transfer_to(self.token_of(poll_info), voter, refunded)?;
+            }
+
+            if penalty > 0 && self.transfer_to(self.token_of(poll_info), self.treasury, penalty).is_ok() {
+                self.env().emit_event(FeeCollected {
+                    poll_id: poll_id.into(),
+                    voter,
+                    treasury: self.treasury,
+                    amount: penalty,
+                });
+            }
+
+            Ok(refunded)
+        }
+
+        // Tally votes of the voter, boosted when the voter has an active stake
+        fn boosted(&self, voter: AccountId, number_of_votes: Balance) -> Result<Balance, Error> {
+            let Some(staking_contract) = self.staking_contract else {
+                return Ok(number_of_votes)
+            };
+
+            if self.staked_of(staking_contract, voter)? == 0 {
+                return Ok(number_of_votes)
+            }
+
+            number_of_votes
+                .checked_mul(Balance::from(self.boost_multiplier))
+                .map(|votes| votes / Balance::from(BOOST_DENOMINATOR))
+                .ok_or(Error::ArithmeticOverflow)
+        }
+
+        // Cost of votes^2 / vote_unit base units, split as votes = q * vote_unit + r so that the square never overflows on its own
+        fn quadratic_cost(poll_info: &PollInfo, number_of_votes: Balance) -> Result<Balance, Error> {
+            let vote_unit = poll_info.settings.vote_unit.max(1);
+            let whole_votes = number_of_votes / vote_unit;
+            let fraction = number_of_votes % vote_unit;
+
+            let fraction_squared = fraction.checked_mul(fraction).ok_or(Error::NotEnoughFunds)?;
+            let cost = whole_votes
+                .checked_mul(number_of_votes)
+                .and_then(|cost| cost.checked_add(whole_votes.checked_mul(fraction)?))
+                .and_then(|cost| cost.checked_add(fraction_squared / vote_unit))
+                .ok_or(Error::NotEnoughFunds)?;
+
+            match poll_info.settings.rounding {
+                Rounding::Ceil if fraction_squared % vote_unit != 0 => cost.checked_add(1).ok_or(Error::NotEnoughFunds),
+                _ => Ok(cost),
+            }
+        }
+
+        // Votes scaled by the share of the poll window that is still left
+        fn decayed(&self, poll_info: &PollInfo, number_of_votes: Balance) -> Result<Balance, Error> {
+            let remaining = poll_info.poll_end_timestamp.saturating_sub(self.now(poll_info.settings.window));
+            let duration = poll_info.poll_end_timestamp - poll_info.poll_start_timestamp;
+
+            number_of_votes
+                .checked_mul(Balance::from(remaining))
+                .map(|votes| votes / Balance::from(duration))
+                .ok_or(Error::ArithmeticOverflow)
+        }
+
+        // Amount staked by the owner in the staking contract
+        #[cfg(not(test))]
+        fn staked_of(&self, staking_contract: AccountId, owner: AccountId) -> Result<Balance, Error> {
+            let result = build_call::<DefaultEnvironment>()
+                .call(staking_contract)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(ink::selector_bytes!("Staking::staked_of")))
+                        .push_arg(owner),
+                )
+                .returns::<Balance>()
+                .try_invoke();
+
+            match result {
+                Ok(Ok(staked)) => Ok(staked),
+                _ => Err(Error::StakingQueryFailed),
+            }
+        }
+
+        // Map a PSP22 transfer call result, keeping the token's own error when it returned one
+        #[cfg(not(test))]
+        fn transfer_result(result: ink::env::Result<ink::MessageResult<Result<(), PSP22Error>>>) -> Result<(), Error> {
+            match result {
+                Ok(Ok(Ok(()))) => Ok(()),
+                Ok(Ok(Err(error))) => Err(Error::TokenTransferFailed(error)),
+                Ok(Err(_)) | Err(_) => Err(Error::TokenTransferFailed(PSP22Error::Custom(String::from("call failed")))),
+            }
+        }
+
+        // Tokens the owner approved the contract to spend
+        #[cfg(not(test))]
+        fn allowance(&self, token: AccountId, owner: AccountId) -> Result<Balance, Error> {
+            let result = build_call::<DefaultEnvironment>()
+                .call(token)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(ink::selector_bytes!("PSP22::allowance")))
+                        .push_arg(owner)
+                        .push_arg(self.env().account_id()),
+                )
+                .returns::<Balance>()
+                .try_invoke();
+
+            match result {
+                Ok(Ok(allowance)) => Ok(allowance),
+                _ => Err(Error::TokenQueryFailed),
+            }
+        }
+
+        // Current token balance of the owner
+        #[cfg(not(test))]
+        fn balance_of(&self, token: AccountId, owner: AccountId) -> Result<Balance, Error> {
+            let result = build_call::<DefaultEnvironment>()
+                .call(token)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(ink::selector_bytes!("PSP22::balance_of")))
+                        .push_arg(owner),
+                )
+                .returns::<Balance>()
+                .try_invoke();
+
+            match result {
+                Ok(Ok(balance)) => Ok(balance),
+                _ => Err(Error::TokenQueryFailed),
+            }
+        }
+
+        // Lock tokens of the voter in the contract, returning the amount the contract actually received
+        fn transfer_from_voter(&self, token: AccountId, voter: AccountId, amount: Balance) -> Result<Balance, Error> {
+            let balance_before = self.balance_of(token, self.env().account_id())?;
+
+            self.transfer_from_to(token, voter, self.env().account_id(), amount)?;
+
+            let received_amount = self.balance_of(token, self.env().account_id())?.saturating_sub(balance_before);
+
+            if received_amount == 0 {
+                return Err(Error::NothingReceived)
+            }
+
+            Ok(received_amount)
+        }
+
+        // Move tokens from the voter to the recipient using the contract's allowance
+        fn transfer_from_to(&self, token: AccountId, voter: AccountId, to: AccountId, amount: Balance) -> Result<(), Error> {
+            // Checked up front to give a clearer error than a failed transfer
+            if self.allowance(token, voter)? < amount {
+                return Err(Error::InsufficientAllowance)
+            }
+
+            self.psp22_transfer_from(token, voter, to, amount)
+        }
+
+        // Raw PSP22 transfer_from call
+        #[cfg(not(test))]
+        fn psp22_transfer_from(&self, token: AccountId, voter: AccountId, to: AccountId, amount: Balance) -> Result<(), Error> {
+            let result = build_call::<DefaultEnvironment>()
+                .call(token)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(ink::selector_bytes!("PSP22::transfer_from")))
+                        .push_arg(voter)
+                        .push_arg(to)
+                        .push_arg(amount)
+                        .push_arg(Vec::<u8>::new()),
+                )
+                .returns::<Result<(), PSP22Error>>()
+                .try_invoke();
+
+            Self::transfer_result(result)
+        }
+
+        // Release locked tokens from the contract back to the voter
+        #[cfg(not(test))]
+        fn transfer_to(&self, token: AccountId, to: AccountId, amount: Balance) -> Result<(), Error> {
+            let result = build_call::<DefaultEnvironment>()
+                .call(token)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(ink::selector_bytes!("PSP22::transfer")))
+                        .push_arg(to)
+                        .push_arg(amount)
+                        .push_arg(Vec::<u8>::new()),
+                )
+                .returns::<Result<(), PSP22Error>>()
+                .try_invoke();
+
+            Self::transfer_result(result)
+        }
+        
+    }
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use core::cell::RefCell;
+        use ink::codegen::Env;
+        use ink::env::test::{self, DefaultAccounts};
+        use ink::env::DefaultEnvironment;
+        use std::collections::{BTreeMap, VecDeque};
+
+        type Event = <CeresGovernance as ink::reflect::ContractEventBase>::Type;
+
+        const HOUR: Timestamp = 60 * 60 * 1000;
+        const START: Timestamp = HOUR;
+        const END: Timestamp = 3 * HOUR;
+
+        // Call made back into the contract by a malicious token
+        type Reentry = fn(&mut CeresGovernance) -> Result<(), Error>;
+
+        // Ledger of the PSP22 token and the staking contract, the off-chain environment cannot call other contracts
+        #[derive(Default)]
+        struct MockToken {
+            balances: BTreeMap<(AccountId, AccountId), Balance>,
+            snapshots: BTreeMap<(AccountId, AccountId), Balance>,
+            allowances: BTreeMap<(AccountId, AccountId), Balance>,
+            staked: BTreeMap<AccountId, Balance>,
+            // Share of every transfer burnt by a fee-on-transfer token, in basis points
+            transfer_fee_bps: Balance,
+            // Errors returned by the next transfers, one per transfer
+            failures: VecDeque<PSP22Error>,
+            // Whether the reentrancy lock was held during each transfer
+            locked_during_transfer: Vec<bool>,
+            // Call a malicious token makes back into the contract during the next transfer
+            reentry: Option<Reentry>,
+            // Result of every call made back into the contract
+            reentry_results: Vec<Result<(), Error>>,
+        }
+
+        thread_local! {
+            static MOCK: RefCell<MockToken> = RefCell::new(MockToken::default());
+        }
+
+        impl MockToken {
+            fn balance(&self, token: AccountId, owner: AccountId) -> Balance {
+                self.balances.get(&(token, owner)).copied().unwrap_or_default()
+            }
+        }
+
+        // Test doubles of the token and staking calls made by the contract
+        impl CeresGovernance {
+            pub(super) fn balance_of_at(&self, token: AccountId, owner: AccountId, _block: BlockNumber) -> Result<Balance, Error> {
+                MOCK.with(|mock| {
+                    let mock = mock.borrow();
+                    Ok(mock.snapshots.get(&(token, owner)).copied().unwrap_or_else(|| mock.balance(token, owner)))
+                })
+            }
+
+            pub(super) fn staked_of(&self, _staking_contract: AccountId, owner: AccountId) -> Result<Balance, Error> {
+                MOCK.with(|mock| Ok(mock.borrow().staked.get(&owner).copied().unwrap_or_default()))
+            }
+
+            pub(super) fn allowance(&self, token: AccountId, owner: AccountId) -> Result<Balance, Error> {
+                MOCK.with(|mock| Ok(mock.borrow().allowances.get(&(token, owner)).copied().unwrap_or_default()))
+            }
+
+            pub(super) fn balance_of(&self, token: AccountId, owner: AccountId) -> Result<Balance, Error> {
+                MOCK.with(|mock| Ok(mock.borrow().balance(token, owner)))
+            }
+
+            pub(super) fn psp22_transfer_from(&self, token: AccountId, voter: AccountId, to: AccountId, amount: Balance) -> Result<(), Error> {
+                self.mock_transfer(token, voter, to, amount, true)
+            }
+
+            pub(super) fn transfer_to(&self, token: AccountId, to: AccountId, amount: Balance) -> Result<(), Error> {
+                self.mock_transfer(token, self.env().account_id(), to, amount, false)
+            }
+
+            fn mock_transfer(&self, token: AccountId, from: AccountId, to: AccountId, amount: Balance, spend_allowance: bool) -> Result<(), Error> {
+                // A nested call on chain loads a fresh instance of the contract over the same storage, the token is its caller
+                if let Some(reentry) = MOCK.with(|mock| mock.borrow_mut().reentry.take()) {
+                    let caller = self.env().caller();
+                    set_caller(token);
+                    let result = reentry(&mut CeresGovernance::new(self.ceres_token));
+                    set_caller(caller);
+                    MOCK.with(|mock| mock.borrow_mut().reentry_results.push(result));
+                }
+
+                MOCK.with(|mock| {
+                    let mut mock = mock.borrow_mut();
+                    mock.locked_during_transfer.push(self.locked.get().unwrap_or_default());
+
+                    if let Some(error) = mock.failures.pop_front() {
+                        return Err(Error::TokenTransferFailed(error))
+                    }
+
+                    let from_balance = mock.balance(token, from);
+                    if from_balance < amount {
+                        return Err(Error::TokenTransferFailed(PSP22Error::InsufficientBalance))
+                    }
+
+                    if spend_allowance {
+                        let allowance = mock.allowances.get(&(token, from)).copied().unwrap_or_default();
+                        if allowance < amount {
+                            return Err(Error::TokenTransferFailed(PSP22Error::InsufficientAllowance))
+                        }
+                        mock.allowances.insert((token, from), allowance - amount);
+                    }
+
+                    let burnt = amount * mock.transfer_fee_bps / 10_000;
+                    let to_balance = mock.balance(token, to);
+                    mock.balances.insert((token, from), from_balance - amount);
+                    mock.balances.insert((token, to), to_balance + amount - burnt);
+
+                    Ok(())
+                })
+            }
+        }
+
+        fn accounts() -> DefaultAccounts<DefaultEnvironment> {
+            test::default_accounts::<DefaultEnvironment>()
+        }
+
+        fn contract_id() -> AccountId {
+            AccountId::from([0xc0; 32])
+        }
+
+        fn token() -> AccountId {
+            AccountId::from([0x70; 32])
+        }
+
+        fn set_caller(caller: AccountId) {
+            test::set_caller::<DefaultEnvironment>(caller);
+        }
+
+        fn set_now(timestamp: Timestamp) {
+            test::set_block_timestamp::<DefaultEnvironment>(timestamp);
+        }
+
+        // Give the owner tokens, counted in its snapshot balance and approved to the contract
+        fn fund_in(token: AccountId, owner: AccountId, amount: Balance) {
+            MOCK.with(|mock| {
+                let mut mock = mock.borrow_mut();
+                let balance = mock.balance(token, owner);
+                mock.balances.insert((token, owner), balance + amount);
+                mock.snapshots.insert((token, owner), balance + amount);
+                mock.allowances.insert((token, owner), Balance::MAX);
+            });
+        }
+
+        fn fund(owner: AccountId, amount: Balance) {
+            fund_in(token(), owner, amount);
+        }
+
+        fn balance(owner: AccountId) -> Balance {
+            MOCK.with(|mock| mock.borrow().balance(token(), owner))
+        }
+
+        fn labels(number_of_options: u32) -> Vec<String> {
+            (1..=number_of_options).map(|option| ink::prelude::format!("Option {option}")).collect()
+        }
+
+        // Contract deployed by alice, alice is the only admin
+        fn new_contract() -> CeresGovernance {
+            test::set_callee::<DefaultEnvironment>(contract_id());
+            set_caller(accounts().alice);
+            set_now(0);
+
+            CeresGovernance::new(token())
+        }
+
+        fn create(contract: &mut CeresGovernance, poll_id: &str, number_of_options: u32, settings: PollSettings) -> Result<(), Error> {
+            contract.create_poll(
+                poll_id.into(),
+                number_of_options,
+                START,
+                END,
+                settings,
+                labels(number_of_options),
+                String::from("Title"),
+                String::from("Description"),
+            )
+        }
+
+        // Contract with a three option poll "poll" created by alice that is open for voting
+        fn active_poll(settings: PollSettings) -> CeresGovernance {
+            let mut contract = new_contract();
+            create(&mut contract, "poll", 3, settings).unwrap();
+            set_now(START + 1);
+
+            contract
+        }
+
+        fn events() -> Vec<Event> {
+            test::recorded_events()
+                .map(|event| <Event as Decode>::decode(&mut &event.data[..]).unwrap())
+                .collect()
+        }
+
+        #[ink::test]
+        fn vote_with_zero_votes_is_rejected() {
+            let mut contract = active_poll(PollSettings::default());
+            fund(accounts().bob, 100);
+            set_caller(accounts().bob);
+
+            assert_eq!(contract.vote("poll".into(), 1, 0), Err(Error::InvalidNumberOfVotes));
+            assert_eq!(contract.get_voting_info("poll".into(), accounts().bob), Err(Error::VoterHasNotVoted));
+        }
+
+        #[ink::test]
+        fn option_zero_is_rejected_and_last_option_accepted() {
+            let mut contract = active_poll(PollSettings::default());
+            fund(accounts().bob, 100);
+            set_caller(accounts().bob);
+
+            assert_eq!(contract.vote("poll".into(), 0, 10), Err(Error::InvalidNumberOfOption));
+
+            let voting_info = contract.vote("poll".into(), 3, 10).unwrap();
+            assert_eq!(voting_info.voting_option, 3);
+            assert_eq!(contract.get_option_votes("poll".into(), 3), Ok(10));
+        }
+
+        #[ink::test]
+        fn options_above_number_of_options_are_rejected() {
+            let mut contract = active_poll(PollSettings::default());
+            fund(accounts().bob, 100);
+            fund(accounts().charlie, 100);
+
+            set_caller(accounts().bob);
+            assert_eq!(contract.vote("poll".into(), 4, 10), Err(Error::InvalidNumberOfOption));
+            assert_eq!(contract.get_option_votes("poll".into(), 4), Err(Error::InvalidNumberOfOption));
+
+            set_caller(accounts().charlie);
+            assert!(contract.vote("poll".into(), 3, 10).is_ok());
+        }
+
+        #[ink::test]
+        fn only_admins_can_create_polls() {
+            let mut contract = new_contract();
+
+            set_caller(accounts().bob);
+            assert_eq!(create(&mut contract, "poll", 2, PollSettings::default()), Err(Error::NotAuthorized));
+
+            set_caller(accounts().alice);
+            assert_eq!(create(&mut contract, "poll", 2, PollSettings::default()), Ok(()));
+            assert_eq!(contract.get_poll_creator("poll".into()), Ok(accounts().alice));
+        }
+
+        #[ink::test]
+        fn set_admins_transfers_ownership() {
+            let mut contract = new_contract();
+
+            set_caller(accounts().bob);
+            assert_eq!(contract.set_admins(vec![accounts().bob], 1), Err(Error::NotAuthorized));
+
+            set_caller(accounts().alice);
+            assert_eq!(contract.set_admins(vec![accounts().bob], 1), Ok(()));
+            assert_eq!(contract.get_admins(), vec![accounts().bob]);
+            assert_eq!(create(&mut contract, "first", 2, PollSettings::default()), Err(Error::NotAuthorized));
+
+            set_caller(accounts().bob);
+            assert_eq!(create(&mut contract, "second", 2, PollSettings::default()), Ok(()));
+        }
+
+        #[ink::test]
+        fn withdrawn_voter_cannot_vote_on_an_extended_poll() {
+            let mut contract = active_poll(PollSettings { tie_break: TieBreak::RequireExtension, ..PollSettings::default() });
+            fund(accounts().bob, 100);
+            fund(accounts().charlie, 100);
+
+            set_caller(accounts().bob);
+            contract.vote("poll".into(), 1, 10).unwrap();
+            set_caller(accounts().charlie);
+            contract.vote("poll".into(), 2, 10).unwrap();
+
+            set_now(END + 1);
+            set_caller(accounts().bob);
+            contract.withdrawn("poll".into()).unwrap();
+
+            set_caller(accounts().alice);
+            contract.extend_poll("poll".into(), END + HOUR).unwrap();
+            assert_eq!(contract.poll_status("poll".into()), Ok(PollStatus::Active));
+
+            set_caller(accounts().bob);
+            assert_eq!(contract.vote("poll".into(), 1, 10), Err(Error::AlreadyWithdrawn));
+        }
+
+        #[ink::test]
+        fn votes_per_voter_are_capped_across_votes() {
+            let mut contract = active_poll(PollSettings { max_votes_per_voter: 30, ..PollSettings::default() });
+            fund(accounts().bob, 100);
+            fund(accounts().charlie, 100);
+
+            set_caller(accounts().bob);
+            contract.vote("poll".into(), 1, 10).unwrap();
+            assert_eq!(contract.vote("poll".into(), 1, 20).map(|voting_info| voting_info.number_of_votes), Ok(30));
+            assert_eq!(contract.vote("poll".into(), 1, 1), Err(Error::MaxVotesExceeded));
+
+            set_caller(accounts().charlie);
+            contract.vote("poll".into(), 1, 25).unwrap();
+            assert_eq!(contract.vote("poll".into(), 1, 10), Err(Error::MaxVotesExceeded));
+            assert_eq!(contract.get_option_votes("poll".into(), 1), Ok(55));
+        }
+
+        #[ink::test]
+        fn poll_created_event_carries_the_caller() {
+            let mut contract = new_contract();
+            contract.set_admins(vec![accounts().charlie], 1).unwrap();
+
+            set_caller(accounts().charlie);
+            create(&mut contract, "poll", 2, PollSettings::default()).unwrap();
+
+            let created: Vec<_> = events()
+                .into_iter()
+                .filter_map(|event| match event {
+                    Event::PollCreated(created) => Some(created),
+                    _ => None,
+                })
+                .collect();
+            assert_eq!(created.len(), 1);
+            assert_eq!(created[0].poll_id, "poll");
+            assert_eq!(created[0].creator, accounts().charlie);
+        }
+
+        #[ink::test]
+        fn votes_near_balance_max_overflow_with_an_error() {
+            let mut contract = active_poll(PollSettings::default());
+            fund(accounts().bob, Balance::MAX);
+            set_caller(accounts().bob);
+
+            contract.vote("poll".into(), 1, Balance::MAX - 1).unwrap();
+            assert_eq!(contract.vote("poll".into(), 1, 2), Err(Error::ArithmeticOverflow));
+            assert_eq!(contract.get_total_votes("poll".into()), Ok(Balance::MAX - 1));
+
+            contract.option_votes.insert(("poll", 2), &2);
+            assert_eq!(contract.get_total_votes("poll".into()), Err(Error::ArithmeticOverflow));
+            assert_eq!(contract.is_quorum_reached("poll".into()), Err(Error::ArithmeticOverflow));
+
+            set_now(END + 1);
+            assert_eq!(contract.get_poll_result("poll".into()), Err(Error::ArithmeticOverflow));
+            assert_eq!(contract.close_poll("poll".into()), Err(Error::ArithmeticOverflow));
+        }
+
+        #[ink::test]
+        fn votes_below_min_votes_are_rejected() {
+            let mut contract = active_poll(PollSettings { min_votes: 10, ..PollSettings::default() });
+            fund(accounts().bob, 100);
+            set_caller(accounts().bob);
+
+            assert_eq!(contract.vote("poll".into(), 1, 9), Err(Error::InvalidNumberOfVotes));
+            assert_eq!(contract.vote("poll".into(), 1, 10).map(|voting_info| voting_info.number_of_votes), Ok(10));
+        }
+
+        #[ink::test]
+        fn token_calls_run_under_the_reentrancy_lock() {
+            let mut contract = active_poll(PollSettings::default());
+            fund(accounts().bob, 100);
+            set_caller(accounts().bob);
+
+            contract.vote("poll".into(), 1, 10).unwrap();
+            set_now(END + 1);
+            contract.withdrawn("poll".into()).unwrap();
+
+            // A token reentering during either transfer would find the lock held
+            MOCK.with(|mock| assert_eq!(mock.borrow().locked_during_transfer, vec![true, true]));
+            assert_eq!(contract.locked.get(), Some(false));
+        }
+
+        #[ink::test]
+        fn reentrant_calls_are_rejected() {
+            let mut contract = new_contract();
+            create(&mut contract, "poll", 2, PollSettings { voting_mode: VotingMode::MultiChoice, ..PollSettings::default() }).unwrap();
+            create(&mut contract, "linear", 2, PollSettings::default()).unwrap();
+            fund(accounts().bob, 100);
+            fund(token(), 100);
+            set_caller(accounts().bob);
+            set_now(START + 1);
+
+            // The malicious token calls back into the contract from inside each transfer
+            let reentries: [Reentry; 3] = [
+                |contract| contract.vote("linear".into(), 1, 10).map(|_| ()),
+                |contract| contract.vote_batch(vec![("linear".into(), 1, 10)]).map(|_| ()),
+                |contract| contract.vote_weighted("poll".into(), vec![(1, 10)]),
+            ];
+            for reentry in reentries {
+                MOCK.with(|mock| mock.borrow_mut().reentry = Some(reentry));
+                contract.vote("linear".into(), 1, 10).unwrap();
+            }
+            MOCK.with(|mock| mock.borrow_mut().reentry = Some(|contract| contract.vote("linear".into(), 1, 10).map(|_| ())));
+            contract.vote_weighted("poll".into(), vec![(1, 10)]).unwrap();
+
+            set_now(END + 1);
+            MOCK.with(|mock| mock.borrow_mut().reentry = Some(|contract| contract.withdraw_for("linear".into(), accounts().bob)));
+            contract.withdrawn("linear".into()).unwrap();
+
+            MOCK.with(|mock| {
+                let results = &mock.borrow().reentry_results;
+                assert_eq!(results.len(), 5);
+                assert!(results.iter().all(|result| *result == Err(Error::ReentrancyDetected)));
+            });
+            assert_eq!(contract.get_option_votes("linear".into(), 1), Ok(30));
+            assert_eq!(contract.get_option_votes("poll".into(), 1), Ok(10));
+            assert_eq!(balance(accounts().bob), 90);
+            assert_eq!(contract.get_voting_info("linear".into(), token()), Err(Error::VoterHasNotVoted));
+        }
+
+        #[ink::test]
+        fn failed_vote_releases_the_reentrancy_lock() {
+            let mut contract = active_poll(PollSettings::default());
+            fund(accounts().bob, 100);
+            set_caller(accounts().bob);
+
+            assert_eq!(contract.vote("poll".into(), 1, 1_000), Err(Error::NotEnoughFunds));
+            assert_eq!(contract.locked.get(), Some(false));
+            assert!(contract.vote("poll".into(), 1, 10).is_ok());
+        }
+
+        #[ink::test]
+        fn locked_balances_are_tracked_per_token() {
+            let other_token = AccountId::from([0x71; 32]);
+            let mut contract = new_contract();
+            create(&mut contract, "ceres", 2, PollSettings::default()).unwrap();
+            create(&mut contract, "other", 2, PollSettings { token: Some(other_token), ..PollSettings::default() }).unwrap();
+            fund(accounts().bob, 100);
+            fund_in(other_token, accounts().bob, 100);
+            set_now(START + 1);
+            set_caller(accounts().bob);
+
+            contract.vote("ceres".into(), 1, 10).unwrap();
+            contract.vote("other".into(), 1, 30).unwrap();
+            assert_eq!(contract.get_locked_balance(accounts().bob, token()), 10);
+            assert_eq!(contract.get_locked_balance(accounts().bob, other_token), 30);
+
+            set_now(END + 1);
+            contract.withdrawn("other".into()).unwrap();
+            assert_eq!(contract.get_locked_balance(accounts().bob, token()), 10);
+            assert_eq!(contract.get_locked_balance(accounts().bob, other_token), 0);
+            assert_eq!(contract.get_total_locked(other_token), 0);
+        }
+
+        #[ink::test]
+        fn funds_withdrawn_event_carries_the_poll_id() {
+            let mut contract = active_poll(PollSettings::default());
+            fund(accounts().bob, 100);
+            set_caller(accounts().bob);
+            contract.vote("poll".into(), 1, 10).unwrap();
+
+            set_now(END + 1);
+            contract.withdrawn("poll".into()).unwrap();
+
+            let withdrawn: Vec<_> = events()
+                .into_iter()
+                .filter_map(|event| match event {
+                    Event::FundsWithdrawn(withdrawn) => Some(withdrawn),
+                    _ => None,
+                })
+                .collect();
+            assert_eq!(withdrawn.len(), 1);
+            assert_eq!(withdrawn[0].poll_id, "poll");
+            assert_eq!(withdrawn[0].voter, accounts().bob);
+            assert_eq!(withdrawn[0].amount, 10);
+        }
+
+        #[ink::test]
+        fn poll_count_follows_created_polls() {
+            let mut contract = new_contract();
+            assert_eq!(contract.get_poll_count(), 0);
+
+            for poll_id in ["first", "second", "third"] {
+                create(&mut contract, poll_id, 2, PollSettings::default()).unwrap();
+            }
+            assert_eq!(create(&mut contract, "second", 2, PollSettings::default()), Err(Error::PollIdAlreadyExists));
+
+            assert_eq!(contract.get_poll_count(), 3);
+            assert_eq!(contract.contract_info().poll_count, 3);
+            assert_eq!(contract.get_poll_ids(0, 10), vec!["first", "second", "third"]);
+            assert_eq!(contract.get_poll_ids(1, 1), vec!["second"]);
+            assert!(contract.get_poll_ids(3, 10).is_empty());
+        }
+
+        #[ink::test]
+        fn voted_event_carries_incremental_and_cumulative_votes() {
+            let mut contract = active_poll(PollSettings::default());
+            fund(accounts().bob, 100);
+            set_caller(accounts().bob);
+
+            contract.vote("poll".into(), 1, 10).unwrap();
+            contract.vote("poll".into(), 1, 15).unwrap();
+
+            let voted: Vec<_> = events()
+                .into_iter()
+                .filter_map(|event| match event {
+                    Event::Voted(voted) => Some(voted),
+                    _ => None,
+                })
+                .collect();
+            assert_eq!(voted.len(), 2);
+            assert_eq!((voted[0].number_of_votes, voted[0].cumulative_votes), (10, 10));
+            assert_eq!((voted[1].number_of_votes, voted[1].cumulative_votes), (15, 25));
+        }
+
+        fn create_window(contract: &mut CeresGovernance, poll_id: &str, poll_start_timestamp: Timestamp, poll_end_timestamp: Timestamp) -> Result<(), Error> {
+            contract.create_poll(
+                poll_id.into(),
+                2,
+                poll_start_timestamp,
+                poll_end_timestamp,
+                PollSettings::default(),
+                labels(2),
+                String::from("Title"),
+                String::from("Description"),
+            )
+        }
+
+        #[ink::test]
+        fn polls_longer_than_max_poll_duration_are_rejected() {
+            let mut contract = new_contract();
+
+            assert_eq!(create_window(&mut contract, "at", START, START + DEFAULT_MAX_POLL_DURATION), Ok(()));
+            assert_eq!(
+                create_window(&mut contract, "beyond", START, START + DEFAULT_MAX_POLL_DURATION + 1),
+                Err(Error::PollDurationTooLong)
+            );
+
+            assert_eq!(contract.extend_poll("at".into(), START + DEFAULT_MAX_POLL_DURATION + 1), Err(Error::PollDurationTooLong));
+
+            contract.set_max_poll_duration(2 * HOUR).unwrap();
+            assert_eq!(create_window(&mut contract, "shorter", START, START + 2 * HOUR + 1), Err(Error::PollDurationTooLong));
+        }
+
+        #[ink::test]
+        fn polls_shorter_than_min_poll_duration_are_rejected() {
+            let mut contract = new_contract();
+
+            assert_eq!(create_window(&mut contract, "zero", START, START), Err(Error::InvalidEndTimestamp));
+            assert_eq!(create_window(&mut contract, "short", START, START + DEFAULT_MIN_POLL_DURATION - 1), Err(Error::PollDurationTooShort));
+            assert_eq!(create_window(&mut contract, "minimum", START, START + DEFAULT_MIN_POLL_DURATION), Ok(()));
+        }
+
+        #[ink::test]
+        fn first_and_repeat_votes_take_separate_branches() {
+            let mut contract = active_poll(PollSettings::default());
+            fund(accounts().bob, 100);
+            set_caller(accounts().bob);
+            assert!(!contract.has_voted("poll".into(), accounts().bob));
+
+            let first = contract.vote("poll".into(), 2, 10).unwrap();
+            assert!(first.has_voted);
+            assert_eq!(contract.get_voter_count("poll".into()), Ok(1));
+            assert!(contract.has_voted("poll".into(), accounts().bob));
+
+            let repeat = contract.vote("poll".into(), 2, 5).unwrap();
+            assert_eq!(repeat.number_of_votes, 15);
+            assert_eq!(contract.get_voter_count("poll".into()), Ok(1));
+            assert_eq!(contract.vote("poll".into(), 1, 5), Err(Error::VoteDenied));
+        }
+
+        fn commitment(option: u32, salt: [u8; 32]) -> Hash {
+            let mut hash = <Blake2x256 as ink::env::hash::HashOutput>::Type::default();
+            ink::env::hash_encoded::<Blake2x256, _>(&(option, salt), &mut hash);
+
+            Hash::from(hash)
+        }
+
+        #[ink::test]
+        fn revealed_votes_must_match_the_commitment() {
+            let mut contract = active_poll(PollSettings { reveal_end_timestamp: END + HOUR, ..PollSettings::default() });
+            fund(accounts().bob, 100);
+            set_caller(accounts().bob);
+
+            assert_eq!(contract.vote("poll".into(), 1, 10), Err(Error::VoteDenied));
+            contract.commit_vote("poll".into(), commitment(2, [7; 32])).unwrap();
+
+            set_now(END + 1);
+            assert_eq!(contract.reveal_vote("poll".into(), 2, [8; 32], 10), Err(Error::CommitmentMismatch));
+            assert_eq!(contract.reveal_vote("poll".into(), 1, [7; 32], 10), Err(Error::CommitmentMismatch));
+            assert!(contract.has_committed("poll".into(), accounts().bob));
+
+            assert!(contract.reveal_vote("poll".into(), 2, [7; 32], 10).is_ok());
+            assert_eq!(contract.get_option_votes("poll".into(), 2), Ok(10));
+            assert!(!contract.has_committed("poll".into(), accounts().bob));
+        }
+
+        #[ink::test]
+        fn votes_revealed_after_the_reveal_window_are_rejected() {
+            let mut contract = active_poll(PollSettings { reveal_end_timestamp: END + HOUR, ..PollSettings::default() });
+            fund(accounts().bob, 100);
+            set_caller(accounts().bob);
+            contract.commit_vote("poll".into(), commitment(1, [7; 32])).unwrap();
+
+            set_now(END);
+            assert_eq!(contract.reveal_vote("poll".into(), 1, [7; 32], 10), Err(Error::PollIsNotFinished));
+
+            set_now(END + HOUR + 1);
+            assert_eq!(contract.reveal_vote("poll".into(), 1, [7; 32], 10), Err(Error::RevealWindowClosed));
+
+            set_now(END + HOUR);
+            assert!(contract.reveal_vote("poll".into(), 1, [7; 32], 10).is_ok());
+        }
+
+        #[ink::test]
+        fn reveal_windows_are_bounded_by_max_poll_duration() {
+            let mut contract = new_contract();
+            let settings = |reveal_end_timestamp| PollSettings { reveal_end_timestamp, ..PollSettings::default() };
+
+            assert_eq!(create(&mut contract, "beyond", 2, settings(END + DEFAULT_MAX_POLL_DURATION + 1)), Err(Error::RevealWindowTooLong));
+            assert_eq!(create(&mut contract, "at", 2, settings(END + DEFAULT_MAX_POLL_DURATION)), Ok(()));
+        }
+
+        #[ink::test]
+        fn denied_vote_leaves_storage_unchanged() {
+            let mut contract = active_poll(PollSettings::default());
+            fund(accounts().bob, 100);
+            set_caller(accounts().bob);
+            let voting_info = contract.vote("poll".into(), 1, 10).unwrap();
+            let event_count = events().len();
+
+            assert_eq!(contract.vote("poll".into(), 2, 10), Err(Error::VoteDenied));
+
+            assert_eq!(contract.get_voting_info("poll".into(), accounts().bob), Ok(voting_info));
+            assert_eq!(contract.get_option_votes("poll".into(), 1), Ok(10));
+            assert_eq!(contract.get_option_votes("poll".into(), 2), Ok(0));
+            assert_eq!(contract.get_voter_count("poll".into()), Ok(1));
+            assert_eq!(contract.get_total_locked(token()), 10);
+            assert_eq!(contract.get_stats().votes_cast, 10);
+            assert_eq!(balance(accounts().bob), 90);
+            assert_eq!(events().len(), event_count);
+        }
+
+        #[ink::test]
+        fn block_number_windows_are_bounded_in_blocks() {
+            let mut contract = new_contract();
+            let max_blocks = DEFAULT_MAX_POLL_DURATION / EXPECTED_BLOCK_TIME;
+            let min_blocks = DEFAULT_MIN_POLL_DURATION / EXPECTED_BLOCK_TIME;
+            let mut create_blocks = |poll_id: &str, poll_end_block: Timestamp| {
+                contract.create_poll(
+                    poll_id.into(),
+                    2,
+                    10,
+                    poll_end_block,
+                    PollSettings { window: PollWindow::BlockNumber, ..PollSettings::default() },
+                    labels(2),
+                    String::from("Title"),
+                    String::from("Description"),
+                )
+            };
+
+            assert_eq!(create_blocks("long", 10 + max_blocks + 1), Err(Error::PollDurationTooLong));
+            assert_eq!(create_blocks("short", 10 + min_blocks - 1), Err(Error::PollDurationTooShort));
+            assert_eq!(create_blocks("max", 10 + max_blocks), Ok(()));
+            assert_eq!(create_blocks("min", 10 + min_blocks), Ok(()));
+
+            assert_eq!(contract.extend_poll("min".into(), 10 + max_blocks + 1), Err(Error::PollDurationTooLong));
+            assert_eq!(contract.extend_poll("min".into(), 10 + max_blocks), Ok(()));
+        }
+
+        #[ink::test]
+        fn withdrawal_delays_are_bounded_by_max_poll_duration() {
+            let mut contract = new_contract();
+            let settings = |withdrawal_delay| PollSettings { withdrawal_delay, ..PollSettings::default() };
+
+            assert_eq!(create(&mut contract, "beyond", 2, settings(DEFAULT_MAX_POLL_DURATION + 1)), Err(Error::WithdrawalDelayTooLong));
+            assert_eq!(create(&mut contract, "at", 2, settings(DEFAULT_MAX_POLL_DURATION)), Ok(()));
+        }
+
+        #[ink::test]
+        fn withdrawals_open_once_the_delay_has_passed() {
+            let mut contract = active_poll(PollSettings { withdrawal_delay: HOUR, ..PollSettings::default() });
+            fund(accounts().bob, 100);
+            set_caller(accounts().bob);
+            contract.vote("poll".into(), 1, 10).unwrap();
+
+            set_now(END + HOUR - 1);
+            assert_eq!(contract.withdrawn("poll".into()), Err(Error::WithdrawalNotYetAvailable));
+
+            set_now(END + HOUR);
+            assert_eq!(contract.withdrawn("poll".into()), Ok(()));
+            assert_eq!(balance(accounts().bob), 100);
+        }
+
+        #[ink::test]
+        fn vote_returns_the_updated_voter_state() {
+            let mut contract = active_poll(PollSettings::default());
+            fund(accounts().bob, 100);
+            set_caller(accounts().bob);
+
+            let voting_info = contract.vote("poll".into(), 2, 10).unwrap();
+            assert_eq!(voting_info, VotingInfo {
+                voting_option: 2,
+                number_of_votes: 10,
+                ceres_withdrawn: false,
+                locked_amount: 10,
+                has_voted: true,
+                tally_votes: 10,
+            });
+            assert_eq!(contract.get_voting_info("poll".into(), accounts().bob), Ok(voting_info));
+
+            let voting_info = contract.vote("poll".into(), 2, 5).unwrap();
+            assert_eq!(voting_info.number_of_votes, 15);
+            assert_eq!(contract.get_voting_info("poll".into(), accounts().bob), Ok(voting_info));
+        }
+
+        #[ink::test]
+        fn voter_polls_are_indexed_without_duplicates() {
+            let mut contract = new_contract();
+            for poll_id in ["first", "second"] {
+                create(&mut contract, poll_id, 2, PollSettings::default()).unwrap();
+            }
+            fund(accounts().bob, 100);
+            set_now(START + 1);
+            set_caller(accounts().bob);
+
+            contract.vote("second".into(), 1, 10).unwrap();
+            contract.vote("first".into(), 1, 20).unwrap();
+            contract.vote("second".into(), 1, 5).unwrap();
+
+            assert_eq!(contract.get_voter_polls(accounts().bob, 0, 10), vec!["second", "first"]);
+            assert_eq!(contract.get_voter_polls(accounts().bob, 1, 10), vec!["first"]);
+            assert!(contract.get_voter_polls(accounts().charlie, 0, 10).is_empty());
+            assert!(contract.pending_withdrawals(accounts().bob).is_empty());
+
+            set_now(END + 1);
+            assert_eq!(
+                contract.pending_withdrawals(accounts().bob),
+                vec![(String::from("first"), 20), (String::from("second"), 15)]
+            );
+        }
+
+        #[ink::test]
+        fn vote_fee_is_charged_on_every_vote_path() {
+            let treasury = accounts().django;
+            let mut contract = new_contract();
+            contract.set_fee(1_000).unwrap();
+            contract.set_treasury(treasury).unwrap();
+            create(&mut contract, "poll", 2, PollSettings::default()).unwrap();
+            create(&mut contract, "multi", 2, PollSettings { voting_mode: VotingMode::MultiChoice, ..PollSettings::default() }).unwrap();
+            create(&mut contract, "secret", 2, PollSettings { reveal_end_timestamp: END + HOUR, ..PollSettings::default() }).unwrap();
+            for voter in [accounts().bob, accounts().charlie, accounts().eve] {
+                fund(voter, 1_000);
+            }
+            set_now(START + 1);
+
+            set_caller(accounts().bob);
+            assert_eq!(contract.vote("poll".into(), 1, 100).map(|voting_info| voting_info.locked_amount), Ok(90));
+            assert_eq!(balance(treasury), 10);
+
+            contract.vote_batch(vec![("poll".into(), 1, 50)]).unwrap();
+            assert_eq!(balance(treasury), 15);
+
+            contract.vote_weighted("multi".into(), vec![(1, 50), (2, 30)]).unwrap();
+            assert_eq!(contract.get_option_votes("multi".into(), 1), Ok(45));
+            assert_eq!(contract.get_option_votes("multi".into(), 2), Ok(27));
+            assert_eq!(balance(treasury), 23);
+
+            set_caller(accounts().charlie);
+            contract.delegate("poll".into(), accounts().bob).unwrap();
+            set_caller(accounts().bob);
+            contract.vote_for("poll".into(), accounts().charlie, 2, 200).unwrap();
+            assert_eq!(balance(accounts().charlie), 800);
+            assert_eq!(balance(treasury), 43);
+
+            set_caller(accounts().eve);
+            contract.commit_vote("secret".into(), commitment(1, [1; 32])).unwrap();
+            set_now(END + 1);
+            contract.reveal_vote("secret".into(), 1, [1; 32], 30).unwrap();
+            assert_eq!(contract.get_option_votes("secret".into(), 1), Ok(27));
+            assert_eq!(balance(treasury), 46);
+
+            // Fees are taken under the reentrancy lock like the deposits
+            MOCK.with(|mock| assert!(mock.borrow().locked_during_transfer.iter().all(|locked| *locked)));
+            let fees = events().into_iter().filter(|event| matches!(event, Event::FeeCollected(_))).count();
+            assert_eq!(fees, 5);
+        }
+
+        #[ink::test]
+        fn poll_status_changes_at_the_window_boundaries() {
+            let mut contract = new_contract();
+            create(&mut contract, "poll", 2, PollSettings::default()).unwrap();
+            let status = |contract: &CeresGovernance| contract.poll_status("poll".into()).unwrap();
+
+            set_now(START - 1);
+            assert_eq!(status(&contract), PollStatus::NotStarted);
+            set_now(START);
+            assert_eq!(status(&contract), PollStatus::Active);
+            set_now(END);
+            assert_eq!(status(&contract), PollStatus::Active);
+            set_now(END + 1);
+            assert_eq!(status(&contract), PollStatus::Finished);
+
+            contract.close_poll("poll".into()).unwrap();
+            assert_eq!(status(&contract), PollStatus::Closed);
+            assert_eq!(contract.poll_status("unknown".into()), Err(Error::PollDoesNotExist));
+        }
+
+        #[ink::test]
+        fn draft_and_cancelled_polls_report_their_status() {
+            let mut contract = new_contract();
+            create(&mut contract, "draft", 2, PollSettings { draft: true, ..PollSettings::default() }).unwrap();
+            create(&mut contract, "cancelled", 2, PollSettings::default()).unwrap();
+            contract.cancel_poll("cancelled".into()).unwrap();
+
+            set_now(START);
+            assert_eq!(contract.poll_status("draft".into()), Ok(PollStatus::Draft));
+            assert_eq!(contract.poll_status("cancelled".into()), Ok(PollStatus::Cancelled));
+
+            contract.activate_poll("draft".into()).unwrap();
+            assert_eq!(contract.poll_status("draft".into()), Ok(PollStatus::Active));
+        }
+
+        #[ink::test]
+        fn auto_created_polls_get_distinct_ids() {
+            let mut contract = new_contract();
+            let mut create_auto = || {
+                contract.create_poll_auto(2, START, END, PollSettings::default(), labels(2), String::from("Title"), String::from("Description"))
+            };
+
+            let first = create_auto().unwrap();
+            let second = create_auto().unwrap();
+
+            assert_ne!(first, second);
+            assert_eq!(first.len(), MAX_POLL_ID_LENGTH);
+            assert!(contract.get_poll_info(first).is_ok());
+            assert!(contract.get_poll_info(second).is_ok());
+        }
+
+        #[ink::test]
+        fn poll_ids_are_bounded_in_length() {
+            let mut contract = new_contract();
+            let max_length = "a".repeat(MAX_POLL_ID_LENGTH);
+            let over_length = "a".repeat(MAX_POLL_ID_LENGTH + 1);
+
+            assert_eq!(create(&mut contract, "", 2, PollSettings::default()), Err(Error::InvalidPollId));
+            assert_eq!(create(&mut contract, &over_length, 2, PollSettings::default()), Err(Error::PollIdTooLong));
+            assert_eq!(create(&mut contract, &max_length, 2, PollSettings::default()), Ok(()));
+        }
+
+        #[ink::test]
+        fn vote_on_an_unknown_poll_is_rejected() {
+            let mut contract = active_poll(PollSettings::default());
+            fund(accounts().bob, 100);
+            set_caller(accounts().bob);
+
+            assert_eq!(contract.vote("unknown".into(), 1, 10), Err(Error::PollDoesNotExist));
+            assert_eq!(contract.simulate_vote("unknown".into(), 1, 10, accounts().bob), Err(Error::PollDoesNotExist));
+            assert_eq!(balance(accounts().bob), 100);
+        }
+
+        #[ink::test]
+        fn early_time_decay_votes_count_more_than_late_ones() {
+            let mut contract = active_poll(PollSettings { voting_mode: VotingMode::TimeDecay, ..PollSettings::default() });
+            fund(accounts().bob, 100);
+            fund(accounts().charlie, 100);
+
+            set_now(START);
+            set_caller(accounts().bob);
+            let early = contract.vote("poll".into(), 1, 100).unwrap();
+
+            set_now(START + HOUR);
+            set_caller(accounts().charlie);
+            let late = contract.vote("poll".into(), 2, 100).unwrap();
+
+            assert_eq!((early.tally_votes, early.locked_amount), (100, 100));
+            assert_eq!((late.tally_votes, late.locked_amount), (50, 100));
+            assert_eq!(contract.get_option_votes("poll".into(), 1), Ok(100));
+            assert_eq!(contract.get_option_votes("poll".into(), 2), Ok(50));
+
+            set_now(END + 1);
+            contract.withdrawn("poll".into()).unwrap();
+            assert_eq!(balance(accounts().charlie), 100);
+        }
+
+        #[ink::test]
+        fn token_errors_are_surfaced_by_kind() {
+            let mut contract = active_poll(PollSettings::default());
+            fund(accounts().bob, 100);
+            set_caller(accounts().bob);
+            let kinds = || vec![
+                PSP22Error::Custom(String::from("paused")),
+                PSP22Error::InsufficientBalance,
+                PSP22Error::InsufficientAllowance,
+                PSP22Error::ZeroRecipientAddress,
+                PSP22Error::ZeroSenderAddress,
+                PSP22Error::SafeTransferCheckFailed(String::from("rejected")),
+            ];
+
+            for (failure, expected) in kinds().into_iter().zip(kinds()) {
+                MOCK.with(|mock| mock.borrow_mut().failures.push_back(failure));
+                assert_eq!(contract.vote("poll".into(), 1, 10), Err(Error::TokenTransferFailed(expected)));
+            }
+
+            assert_eq!(contract.get_voting_info("poll".into(), accounts().bob), Err(Error::VoterHasNotVoted));
+            assert_eq!(balance(accounts().bob), 100);
+        }
+
+        #[ink::test]
+        fn vote_above_the_allowance_is_rejected() {
+            let mut contract = active_poll(PollSettings::default());
+            fund(accounts().bob, 100);
+            MOCK.with(|mock| mock.borrow_mut().allowances.insert((token(), accounts().bob), 5));
+            set_caller(accounts().bob);
+
+            assert_eq!(contract.vote("poll".into(), 1, 10), Err(Error::InsufficientAllowance));
+            MOCK.with(|mock| assert!(mock.borrow().locked_during_transfer.is_empty()));
+
+            assert!(contract.vote("poll".into(), 1, 5).is_ok());
+        }
+
+        #[ink::test]
+        fn quorum_can_only_be_lowered() {
+            let mut contract = active_poll(PollSettings { quorum: 100, ..PollSettings::default() });
+
+            assert_eq!(contract.lower_quorum("poll".into(), 100), Err(Error::InvalidQuorum));
+            assert_eq!(contract.lower_quorum("poll".into(), 101), Err(Error::InvalidQuorum));
+            assert_eq!(contract.lower_quorum("poll".into(), 99), Ok(()));
+            assert_eq!(contract.get_poll_info("poll".into()).map(|poll_info| poll_info.settings.quorum), Ok(99));
+
+            set_caller(accounts().bob);
+            assert_eq!(contract.lower_quorum("poll".into(), 50), Err(Error::NotAuthorized));
+
+            set_caller(accounts().alice);
+            set_now(END + 1);
+            assert_eq!(contract.lower_quorum("poll".into(), 50), Err(Error::PollIsFinished));
+        }
+
+        #[ink::test]
+        fn results_and_withdrawals_open_once_the_poll_is_finished() {
+            let mut contract = active_poll(PollSettings::default());
+            fund(accounts().bob, 100);
+            set_caller(accounts().bob);
+            contract.vote("poll".into(), 2, 10).unwrap();
+
+            // The last instant of the window still accepts votes, so nothing is final yet
+            set_now(END);
+            assert_eq!(contract.poll_status("poll".into()), Ok(PollStatus::Active));
+            assert_eq!(contract.get_poll_result("poll".into()), Err(Error::PollIsNotFinished));
+            assert_eq!(contract.get_poll_breakdown("poll".into()), Err(Error::PollIsNotFinished));
+            assert_eq!(contract.withdrawn("poll".into()), Err(Error::PollIsNotFinished));
+            assert_eq!(contract.close_poll("poll".into()), Err(Error::PollIsNotFinished));
+
+            set_now(END + 1);
+            assert_eq!(contract.get_poll_result("poll".into()), Ok((2, 10)));
+            assert_eq!(contract.get_poll_breakdown("poll".into()), Ok(vec![(1, 0), (2, 10), (3, 0)]));
+            assert_eq!(contract.withdrawn("poll".into()), Ok(()));
+            assert_eq!(contract.close_poll("poll".into()), Ok(()));
+            assert_eq!(contract.get_poll_result("poll".into()), Ok((2, 10)));
+        }
+
+        #[ink::test]
+        fn results_of_commit_reveal_polls_wait_for_the_reveal_window() {
+            let mut contract = active_poll(PollSettings { reveal_end_timestamp: END + HOUR, ..PollSettings::default() });
+
+            set_now(END + HOUR);
+            assert_eq!(contract.get_poll_result("poll".into()), Err(Error::PollIsNotFinished));
+            assert_eq!(contract.get_poll_breakdown("poll".into()), Err(Error::PollIsNotFinished));
+            assert_eq!(contract.close_poll("poll".into()), Err(Error::PollIsNotFinished));
+
+            set_now(END + HOUR + 1);
+            assert_eq!(contract.get_poll_breakdown("poll".into()), Ok(vec![(1, 0), (2, 0), (3, 0)]));
+            assert_eq!(contract.close_poll("poll".into()), Ok(()));
+        }
+
+        #[ink::test]
+        fn cancelled_polls_have_no_result_but_refund_at_once() {
+            let mut contract = active_poll(PollSettings::default());
+            fund(accounts().bob, 100);
+            set_caller(accounts().bob);
+            contract.vote("poll".into(), 1, 10).unwrap();
+
+            set_caller(accounts().alice);
+            contract.cancel_poll("poll".into()).unwrap();
+
+            set_now(END + 1);
+            assert_eq!(contract.get_poll_result("poll".into()), Err(Error::PollCancelled));
+            assert_eq!(contract.get_poll_breakdown("poll".into()), Err(Error::PollCancelled));
+            assert_eq!(contract.close_poll("poll".into()), Err(Error::PollCancelled));
+
+            set_now(START + 2);
+            set_caller(accounts().bob);
+            assert_eq!(contract.withdrawn("poll".into()), Ok(()));
+            assert_eq!(balance(accounts().bob), 100);
+        }
+
+        #[ink::test]
+        fn one_per_account_polls_count_accounts() {
+            let mut contract = active_poll(PollSettings { voting_mode: VotingMode::OnePerAccount, ..PollSettings::default() });
+
+            set_caller(accounts().bob);
+            let voting_info = contract.vote("poll".into(), 1, 500).unwrap();
+            assert_eq!((voting_info.number_of_votes, voting_info.locked_amount), (1, 0));
+            assert_eq!(contract.vote("poll".into(), 1, 1), Err(Error::VoteDenied));
+
+            set_caller(accounts().charlie);
+            contract.vote("poll".into(), 1, 7).unwrap();
+            set_caller(accounts().eve);
+            contract.vote("poll".into(), 2, 1_000_000).unwrap();
+
+            assert_eq!(contract.get_option_votes("poll".into(), 1), Ok(2));
+            assert_eq!(contract.get_option_votes("poll".into(), 2), Ok(1));
+            assert_eq!(contract.get_total_locked(token()), 0);
+            MOCK.with(|mock| assert!(mock.borrow().locked_during_transfer.is_empty()));
+        }
+
+        #[ink::test]
+        fn set_code_hash_is_owner_gated() {
+            let mut contract = new_contract();
+
+            set_caller(accounts().bob);
+            assert_eq!(contract.set_code_hash(Hash::from([0x42; 32])), Err(Error::NotAuthorized));
+
+            set_caller(accounts().alice);
+            contract.set_admins(vec![accounts().alice, accounts().bob], 2).unwrap();
+            assert_eq!(contract.set_code_hash(Hash::from([0x42; 32])), Err(Error::InsufficientConfirmations));
+            assert_eq!(contract.get_admins(), vec![accounts().alice, accounts().bob]);
+        }
+
+        #[ink::test]
+        fn votes_stop_once_the_cap_is_reached() {
+            let mut contract = active_poll(PollSettings { max_total_votes: 30, ..PollSettings::default() });
+            fund(accounts().bob, 100);
+            fund(accounts().charlie, 100);
+
+            set_caller(accounts().bob);
+            contract.vote("poll".into(), 1, 20).unwrap();
+            set_caller(accounts().charlie);
+            contract.vote("poll".into(), 2, 10).unwrap();
+
+            assert_eq!(contract.vote("poll".into(), 2, 1), Err(Error::PollVoteCapReached));
+            set_caller(accounts().bob);
+            assert_eq!(contract.vote("poll".into(), 1, 1), Err(Error::PollVoteCapReached));
+            assert_eq!(contract.get_option_votes("poll".into(), 1), Ok(20));
+            assert_eq!(contract.get_option_votes("poll".into(), 2), Ok(10));
+        }
+
+        #[ink::test]
+        fn proposed_polls_belong_to_the_contract() {
+            let mut contract = new_contract();
+            contract.set_admins(vec![accounts().alice, accounts().bob], 2).unwrap();
+
+            let proposal_id = contract.propose_admin_action(AdminAction::CreatePoll {
+                poll_id: "poll".into(),
+                number_of_options: 2,
+                poll_start_timestamp: START,
+                poll_end_timestamp: END,
+                settings: PollSettings::default(),
+                option_labels: labels(2),
+                title: String::from("Title"),
+                description: String::from("Description"),
+            }).unwrap();
+            set_caller(accounts().bob);
+            contract.confirm_admin_action(proposal_id).unwrap();
+
+            assert_eq!(contract.get_poll_creator("poll".into()), Ok(contract_id()));
+            let creators: Vec<_> = events()
+                .into_iter()
+                .filter_map(|event| match event {
+                    Event::PollCreated(created) => Some(created.creator),
+                    _ => None,
+                })
+                .collect();
+            assert_eq!(creators, vec![contract_id()]);
+
+            // The confirming admin gets no creator rights over the poll
+            assert_eq!(contract.cancel_poll("poll".into()), Err(Error::InsufficientConfirmations));
+        }
+
+        #[ink::test]
+        fn only_the_creator_manages_a_poll_directly() {
+            let mut contract = new_contract();
+            contract.set_admins(vec![accounts().charlie], 1).unwrap();
+            set_caller(accounts().charlie);
+            create(&mut contract, "poll", 2, PollSettings::default()).unwrap();
+            contract.set_admins(vec![accounts().alice, accounts().bob], 2).unwrap();
+
+            assert_eq!(contract.get_poll_creator("poll".into()), Ok(accounts().charlie));
+
+            set_caller(accounts().eve);
+            assert_eq!(contract.cancel_poll("poll".into()), Err(Error::NotPollCreator));
+
+            set_caller(accounts().charlie);
+            assert_eq!(contract.cancel_poll("poll".into()), Ok(()));
+        }
+
+        #[ink::test]
+        fn option_count_and_label_bytes_are_bounded() {
+            let mut contract = new_contract();
+            let create_with = |contract: &mut CeresGovernance, poll_id: &str, option_labels: Vec<String>| {
+                contract.create_poll(
+                    poll_id.into(),
+                    option_labels.len() as u32,
+                    START,
+                    END,
+                    PollSettings::default(),
+                    option_labels,
+                    String::from("Title"),
+                    String::from("Description"),
+                )
+            };
+            let label_bytes = MAX_OPTION_LABELS_LENGTH / DEFAULT_MAX_OPTIONS as usize;
+
+            assert_eq!(create_with(&mut contract, "max", vec!["x".repeat(label_bytes); DEFAULT_MAX_OPTIONS as usize]), Ok(()));
+            assert_eq!(contract.get_poll_options("max".into()).map(|labels| labels.len()), Ok(DEFAULT_MAX_OPTIONS as usize));
+
+            assert_eq!(create_with(&mut contract, "too_many", labels(DEFAULT_MAX_OPTIONS + 1)), Err(Error::TooManyOptions));
+
+            let mut too_long = vec!["x".repeat(label_bytes); DEFAULT_MAX_OPTIONS as usize];
+            too_long[0].push('x');
+            assert_eq!(create_with(&mut contract, "too_long", too_long), Err(Error::InvalidOptionLabels));
+            assert_eq!(
+                create_with(&mut contract, "too_long", vec!["x".repeat(MAX_OPTION_LABEL_LENGTH); DEFAULT_MAX_OPTIONS as usize]),
+                Err(Error::InvalidOptionLabels),
+            );
+        }
+
+        #[ink::test]
+        fn abstain_votes_count_toward_quorum_but_never_win() {
+            let mut contract = active_poll(PollSettings { allow_abstain: true, quorum: 50, ..PollSettings::default() });
+            fund(accounts().bob, 100);
+            fund(accounts().charlie, 100);
+
+            set_caller(accounts().bob);
+            contract.vote("poll".into(), 0, 40).unwrap();
+            set_caller(accounts().charlie);
+            contract.vote("poll".into(), 2, 10).unwrap();
+
+            set_now(END + 1);
+            assert_eq!(contract.get_option_votes("poll".into(), 0), Ok(40));
+            assert_eq!(contract.get_poll_result("poll".into()), Ok((2, 10)));
+        }
+
+        #[ink::test]
+        fn abstain_is_rejected_unless_the_poll_allows_it() {
+            let mut contract = active_poll(PollSettings::default());
+            fund(accounts().bob, 100);
+
+            set_caller(accounts().bob);
+            assert_eq!(contract.vote("poll".into(), 0, 10), Err(Error::InvalidNumberOfOption));
+        }
+
+        #[ink::test]
+        fn votes_open_once_the_start_buffer_has_passed() {
+            let buffer = 10 * 60 * 1000;
+            let mut contract = new_contract();
+            create(&mut contract, "poll", 2, PollSettings { start_buffer: buffer, ..PollSettings::default() }).unwrap();
+            fund(accounts().bob, 100);
+            set_caller(accounts().bob);
+
+            set_now(START);
+            assert_eq!(contract.vote("poll".into(), 1, 10), Err(Error::PollIsNotStarted));
+            set_now(START + buffer - 1);
+            assert_eq!(contract.vote("poll".into(), 1, 10), Err(Error::PollIsNotStarted));
+            set_now(START + buffer);
+            assert_eq!(contract.vote("poll".into(), 1, 10).map(|voting_info| voting_info.number_of_votes), Ok(10));
+        }
+
+        #[ink::test]
+        fn quadratic_cost_rounds_fractional_units() {
+            let mut contract = new_contract();
+            for (poll_id, rounding) in [("floor", Rounding::Floor), ("ceil", Rounding::Ceil)] {
+                let settings = PollSettings { voting_mode: VotingMode::Quadratic, vote_unit: 10, rounding, ..PollSettings::default() };
+                create(&mut contract, poll_id, 2, settings).unwrap();
+            }
+            set_now(START + 1);
+            fund(accounts().bob, 1_000);
+            fund(accounts().charlie, 1_000);
+
+            // 15 * 15 / 10 leaves half a base unit, 20 * 20 / 10 divides exactly
+            for (poll_id, odd_cost) in [("floor", 22), ("ceil", 23)] {
+                set_caller(accounts().bob);
+                assert_eq!(contract.vote(poll_id.into(), 1, 15).map(|voting_info| voting_info.locked_amount), Ok(odd_cost));
+                set_caller(accounts().charlie);
+                assert_eq!(contract.vote(poll_id.into(), 2, 20).map(|voting_info| voting_info.locked_amount), Ok(40));
+            }
+            assert_eq!(balance(accounts().bob), 1_000 - 22 - 23);
+            assert_eq!(balance(accounts().charlie), 1_000 - 40 - 40);
+        }
+
+        #[ink::test]
+        fn fee_on_transfer_votes_count_what_arrived() {
+            let mut contract = new_contract();
+            create(&mut contract, "linear", 2, PollSettings::default()).unwrap();
+            create(&mut contract, "quadratic", 2, PollSettings { voting_mode: VotingMode::Quadratic, ..PollSettings::default() }).unwrap();
+            create(&mut contract, "weighted", 2, PollSettings { voting_mode: VotingMode::MultiChoice, ..PollSettings::default() }).unwrap();
+            set_now(START + 1);
+            fund(accounts().bob, 1_000);
+            MOCK.with(|mock| mock.borrow_mut().transfer_fee_bps = 1_000);
+            set_caller(accounts().bob);
+
+            let voting_info = contract.vote("linear".into(), 1, 100).unwrap();
+            assert_eq!((voting_info.number_of_votes, voting_info.locked_amount), (90, 90));
+            assert_eq!(contract.get_option_votes("linear".into(), 1), Ok(90));
+
+            // 10 votes cost 100, 90 arrives and pays for 9 votes at 81
+            let voting_info = contract.vote("quadratic".into(), 1, 10).unwrap();
+            assert_eq!((voting_info.number_of_votes, voting_info.locked_amount, voting_info.tally_votes), (9, 90, 9));
+            assert_eq!(contract.get_option_votes("quadratic".into(), 1), Ok(9));
+
+            contract.vote_weighted("weighted".into(), vec![(1, 60), (2, 40)]).unwrap();
+            assert_eq!(contract.get_option_votes("weighted".into(), 1), Ok(54));
+            assert_eq!(contract.get_option_votes("weighted".into(), 2), Ok(36));
+            assert_eq!(contract.get_voting_info("weighted".into(), accounts().bob).map(|voting_info| voting_info.number_of_votes), Ok(90));
+
+            assert_eq!(contract.get_locked_balance(accounts().bob, token()), 270);
+            assert_eq!(contract.get_total_locked(token()), 270);
+            assert_eq!(contract.get_stats().votes_cast, 90 + 9 + 90);
+        }
+
+        #[ink::test]
+        fn creators_below_the_minimum_balance_are_rejected() {
+            let mut contract = new_contract();
+            contract.set_min_balance_to_create(100).unwrap();
+
+            assert_eq!(create(&mut contract, "poll", 2, PollSettings::default()), Err(Error::InsufficientBalanceToCreate));
+            fund(accounts().alice, 99);
+            assert_eq!(create(&mut contract, "poll", 2, PollSettings::default()), Err(Error::InsufficientBalanceToCreate));
+            fund(accounts().alice, 1);
+            assert_eq!(create(&mut contract, "poll", 2, PollSettings::default()), Ok(()));
+        }
+
+        #[ink::test]
+        fn two_and_three_way_ties_follow_the_tie_break() {
+            let mut contract = new_contract();
+            for (poll_id, tie_break) in [("lowest", TieBreak::LowestIndex), ("none", TieBreak::NoWinner), ("extend", TieBreak::RequireExtension)] {
+                create(&mut contract, &ink::prelude::format!("{poll_id}-2"), 2, PollSettings { tie_break, ..PollSettings::default() }).unwrap();
+                create(&mut contract, &ink::prelude::format!("{poll_id}-3"), 3, PollSettings { tie_break, ..PollSettings::default() }).unwrap();
+            }
+            set_now(START + 1);
+            let voters = [accounts().bob, accounts().charlie, accounts().django];
+            for voter in voters {
+                fund(voter, 1_000);
+            }
+
+            for poll_id in ["lowest", "none", "extend"] {
+                for number_of_options in [2, 3] {
+                    let poll_id = ink::prelude::format!("{poll_id}-{number_of_options}");
+                    for (voter, option) in voters.iter().zip((1..=number_of_options).rev()) {
+                        set_caller(*voter);
+                        contract.vote(poll_id.clone(), option, 10).unwrap();
+                    }
+                }
+            }
+
+            set_now(END + 1);
+            set_caller(accounts().bob);
+            for number_of_options in [2, 3] {
+                let poll_id = |prefix: &str| ink::prelude::format!("{prefix}-{number_of_options}");
+
+                assert_eq!(contract.get_poll_result(poll_id("lowest")), Ok((1, 10)));
+                assert_eq!(contract.withdrawn(poll_id("lowest")), Ok(()));
+
+                assert_eq!(contract.get_poll_result(poll_id("none")), Err(Error::TieUnresolved));
+                assert_eq!(contract.withdrawn(poll_id("none")), Ok(()));
+
+                // The tie only holds back the result, never the funds
+                assert_eq!(contract.get_poll_result(poll_id("extend")), Err(Error::TieRequiresExtension));
+                assert_eq!(contract.withdrawn(poll_id("extend")), Ok(()));
+            }
+            assert_eq!(balance(accounts().bob), 1_000);
+
+            // An extension lets a new vote break the tie
+            set_caller(accounts().alice);
+            contract.extend_poll("extend-3".into(), END + HOUR).unwrap();
+            set_caller(accounts().charlie);
+            contract.vote("extend-3".into(), 2, 1).unwrap();
+
+            set_now(END + HOUR + 1);
+            assert_eq!(contract.get_poll_result("extend-3".into()), Ok((2, 11)));
+            set_caller(accounts().charlie);
+            assert_eq!(contract.withdrawn("extend-3".into()), Ok(()));
+        }
+
+        #[ink::test]
+        fn polls_without_votes_for_an_option_are_not_tied() {
+            let mut contract = new_contract();
+            contract.set_max_poll_duration(END - START).unwrap();
+            create(&mut contract, "poll", 2, PollSettings { allow_abstain: true, tie_break: TieBreak::RequireExtension, ..PollSettings::default() }).unwrap();
+            fund(accounts().bob, 100);
+            set_now(START + 1);
+            set_caller(accounts().bob);
+            contract.vote("poll".into(), 0, 10).unwrap();
+
+            set_now(END + 1);
+            assert_eq!(contract.withdrawn("poll".into()), Ok(()));
+            assert_eq!(balance(accounts().bob), 100);
+
+            set_caller(accounts().alice);
+            assert_eq!(contract.extend_poll("poll".into(), END + HOUR), Err(Error::PollIsFinished));
+            assert!(contract.close_poll("poll".into()).is_ok());
+        }
+
+        #[ink::test]
+        fn both_quorums_must_be_met() {
+            let mut contract = new_contract();
+            let settings = PollSettings { quorum: 50, min_unique_voters: 2, ..PollSettings::default() };
+            for poll_id in ["tokens", "voters", "both"] {
+                create(&mut contract, poll_id, 2, settings.clone()).unwrap();
+            }
+            set_now(START + 1);
+            fund(accounts().bob, 1_000);
+            fund(accounts().charlie, 1_000);
+
+            set_caller(accounts().bob);
+            contract.vote("tokens".into(), 1, 60).unwrap();
+            contract.vote("voters".into(), 1, 10).unwrap();
+            contract.vote("both".into(), 1, 40).unwrap();
+            set_caller(accounts().charlie);
+            contract.vote("voters".into(), 2, 5).unwrap();
+            contract.vote("both".into(), 2, 10).unwrap();
+
+            set_now(END + 1);
+            assert_eq!(contract.get_poll_result("tokens".into()), Err(Error::VoterQuorumNotReached));
+            assert_eq!(contract.get_poll_result("voters".into()), Err(Error::QuorumNotReached));
+            assert_eq!(contract.get_poll_result("both".into()), Ok((1, 40)));
+        }
+
+        #[ink::test]
+        fn events_carry_the_block_timestamp() {
+            let mut contract = active_poll(PollSettings::default());
+            fund(accounts().bob, 100);
+
+            set_now(START + 1_234);
+            set_caller(accounts().bob);
+            contract.vote("poll".into(), 1, 10).unwrap();
+            set_now(END + 5_678);
+            contract.withdrawn("poll".into()).unwrap();
+
+            let timestamps: Vec<_> = events()
+                .into_iter()
+                .filter_map(|event| match event {
+                    Event::Voted(voted) => Some(voted.timestamp),
+                    Event::FundsWithdrawn(withdrawn) => Some(withdrawn.timestamp),
+                    _ => None,
+                })
+                .collect();
+            assert_eq!(timestamps, vec![START + 1_234, END + 5_678]);
+        }
+
+        #[ink::test]
+        fn cancel_penalty_is_capped() {
+            let mut contract = new_contract();
+
+            assert_eq!(contract.set_cancel_penalty(MAX_CANCEL_PENALTY_BPS + 1), Err(Error::InvalidFee));
+            assert_eq!(contract.set_cancel_penalty(MAX_CANCEL_PENALTY_BPS), Ok(()));
+            assert_eq!(contract.get_cancel_penalty(), MAX_CANCEL_PENALTY_BPS);
+        }
+
+        #[ink::test]
+        fn cancel_penalty_splits_the_refund_exactly() {
+            let mut contract = active_poll(PollSettings::default());
+            contract.set_treasury(accounts().eve).unwrap();
+            contract.set_cancel_penalty(250).unwrap();
+            fund(accounts().bob, 1_000);
+            fund(accounts().charlie, 1_000);
+
+            set_caller(accounts().bob);
+            contract.vote("poll".into(), 1, 1_000).unwrap();
+            set_caller(accounts().charlie);
+            contract.vote("poll".into(), 2, 333).unwrap();
+
+            set_caller(accounts().alice);
+            contract.cancel_poll("poll".into()).unwrap();
+
+            // 2.5% of 1_000 is 25, 2.5% of 333 rounds down to 8 in the voter's favour
+            set_caller(accounts().bob);
+            assert_eq!(contract.preview_withdraw("poll".into(), accounts().bob), Ok(975));
+            contract.withdrawn("poll".into()).unwrap();
+            set_caller(accounts().charlie);
+            contract.withdrawn("poll".into()).unwrap();
+
+            assert_eq!(balance(accounts().bob), 975);
+            assert_eq!(balance(accounts().charlie), 1_000 - 8);
+            assert_eq!(balance(accounts().eve), 25 + 8);
+            assert_eq!(balance(contract_id()), 0);
+            assert_eq!(contract.get_total_locked(token()), 0);
+        }
+
+        #[ink::test]
+        fn finished_polls_cannot_be_cancelled() {
+            let mut contract = active_poll(PollSettings::default());
+            fund(accounts().bob, 100);
+            set_caller(accounts().bob);
+            contract.vote("poll".into(), 1, 10).unwrap();
+
+            set_caller(accounts().alice);
+            set_now(END + 1);
+            assert_eq!(contract.cancel_poll("poll".into()), Err(Error::PollIsFinished));
+            contract.close_poll("poll".into()).unwrap();
+            assert_eq!(contract.cancel_poll("poll".into()), Err(Error::PollIsFinished));
+            assert_eq!(contract.poll_status("poll".into()), Ok(PollStatus::Closed));
+        }
+
+        #[ink::test]
+        fn anyone_can_withdraw_for_a_voter() {
+            let mut contract = active_poll(PollSettings::default());
+            fund(accounts().bob, 100);
+            set_caller(accounts().bob);
+            contract.vote("poll".into(), 1, 40).unwrap();
+
+            set_now(END + 1);
+            set_caller(accounts().eve);
+            assert_eq!(contract.withdraw_for("poll".into(), accounts().bob), Ok(()));
+
+            assert_eq!(balance(accounts().bob), 100);
+            assert_eq!(balance(accounts().eve), 0);
+            assert_eq!(contract.get_voting_info("poll".into(), accounts().bob).map(|voting_info| voting_info.ceres_withdrawn), Ok(true));
+            assert_eq!(contract.withdraw_for("poll".into(), accounts().bob), Err(Error::FundsAlreadyWithdrawn));
+        }
+
+        #[ink::test]
+        fn polls_are_paged_by_category() {
+            let mut contract = new_contract();
+            for (poll_id, category) in [("a", "treasury"), ("b", "tech"), ("c", "treasury"), ("d", ""), ("e", "treasury")] {
+                create(&mut contract, poll_id, 2, PollSettings { category: category.into(), ..PollSettings::default() }).unwrap();
+            }
+
+            assert_eq!(contract.get_polls_by_category("treasury".into(), 0, 10), vec!["a", "c", "e"]);
+            assert_eq!(contract.get_polls_by_category("treasury".into(), 1, 1), vec!["c"]);
+            assert_eq!(contract.get_polls_by_category("treasury".into(), 3, 10), Vec::<String>::new());
+            assert_eq!(contract.get_polls_by_category("tech".into(), 0, u32::MAX), vec!["b"]);
+            assert_eq!(contract.get_polls_by_category("".into(), 0, 10), Vec::<String>::new());
+        }
+
+        #[ink::test]
+        fn blank_and_control_character_poll_ids_are_rejected() {
+            let mut contract = new_contract();
+
+            for poll_id in [" ", "  \t ", "\u{3000}", "poll\n", "po\u{0}ll", "\u{7f}poll"] {
+                assert_eq!(create(&mut contract, poll_id, 2, PollSettings::default()), Err(Error::InvalidPollId));
+            }
+            assert_eq!(create(&mut contract, "my poll", 2, PollSettings::default()), Ok(()));
+        }
+
+        #[ink::test]
+        fn failed_refunds_leave_the_escrow_for_a_retry() {
+            let mut contract = active_poll(PollSettings::default());
+            fund(accounts().bob, 100);
+            fund(accounts().charlie, 100);
+            set_caller(accounts().charlie);
+            contract.vote("poll".into(), 2, 40).unwrap();
+            set_caller(accounts().bob);
+            contract.vote("poll".into(), 1, 40).unwrap();
+
+            set_now(END + 1);
+            MOCK.with(|mock| mock.borrow_mut().failures.push_back(PSP22Error::InsufficientBalance));
+            assert_eq!(contract.withdrawn("poll".into()), Err(Error::TokenTransferFailed(PSP22Error::InsufficientBalance)));
+            let voting_info = contract.get_voting_info("poll".into(), accounts().bob).unwrap();
+            assert_eq!((voting_info.ceres_withdrawn, voting_info.locked_amount), (false, 40));
+            assert_eq!(contract.get_locked_balance(accounts().bob, token()), 40);
+            assert_eq!(contract.get_total_locked(token()), 80);
+
+            assert_eq!(contract.withdrawn("poll".into()), Ok(()));
+            assert_eq!(balance(accounts().bob), 100);
+            assert_eq!(contract.withdrawn("poll".into()), Err(Error::FundsAlreadyWithdrawn));
+            assert_eq!(balance(accounts().bob), 100);
+
+            set_caller(accounts().charlie);
+            MOCK.with(|mock| mock.borrow_mut().failures.push_back(PSP22Error::InsufficientBalance));
+            assert_eq!(contract.withdraw_partial("poll".into(), 15), Err(Error::TokenTransferFailed(PSP22Error::InsufficientBalance)));
+            let voting_info = contract.get_voting_info("poll".into(), accounts().charlie).unwrap();
+            assert_eq!((voting_info.ceres_withdrawn, voting_info.locked_amount), (false, 40));
+            assert_eq!(contract.get_locked_balance(accounts().charlie, token()), 40);
+            assert_eq!(contract.get_total_locked(token()), 40);
+
+            assert_eq!(contract.withdraw_partial("poll".into(), 15), Ok(()));
+            assert_eq!(balance(accounts().charlie), 75);
+            assert_eq!(contract.get_total_locked(token()), 25);
+        }
+
+        #[ink::test]
+        fn vote_bounds_apply_before_the_fee() {
+            let mut contract = active_poll(PollSettings { min_votes: 50, vote_step: 10, ..PollSettings::default() });
+            contract.set_treasury(accounts().eve).unwrap();
+            contract.set_fee(1_000).unwrap();
+            fund(accounts().bob, 100);
+            set_caller(accounts().bob);
+
+            assert_eq!(contract.vote("poll".into(), 1, 40), Err(Error::InvalidNumberOfVotes));
+            assert_eq!(contract.vote("poll".into(), 1, 55), Err(Error::InvalidVoteStep));
+
+            // 50 supplied meets both bounds even though only 45 is counted after the fee
+            let voting_info = contract.vote("poll".into(), 1, 50).unwrap();
+            assert_eq!((voting_info.number_of_votes, voting_info.locked_amount), (45, 45));
+            assert_eq!(contract.get_option_votes("poll".into(), 1), Ok(45));
+            assert_eq!(balance(accounts().bob), 50);
+            assert_eq!(balance(accounts().eve), 5);
+        }
+
+        #[ink::test]
+        fn poll_duration_bounds_stay_satisfiable() {
+            let mut contract = new_contract();
+
+            assert_eq!(contract.set_max_poll_duration(0), Err(Error::InvalidPollDurationBounds));
+            assert_eq!(contract.set_max_poll_duration(DEFAULT_MIN_POLL_DURATION - 1), Err(Error::InvalidPollDurationBounds));
+            assert_eq!(contract.set_min_poll_duration(DEFAULT_MAX_POLL_DURATION + 1), Err(Error::InvalidPollDurationBounds));
+
+            assert_eq!(contract.set_max_poll_duration(DEFAULT_MIN_POLL_DURATION), Ok(()));
+            assert_eq!(contract.set_min_poll_duration(DEFAULT_MIN_POLL_DURATION + 1), Err(Error::InvalidPollDurationBounds));
+            assert_eq!(contract.set_min_poll_duration(0), Ok(()));
+            assert_eq!((contract.get_min_poll_duration(), contract.get_max_poll_duration()), (0, DEFAULT_MIN_POLL_DURATION));
+            assert_eq!(create(&mut contract, "poll", 2, PollSettings::default()), Err(Error::PollDurationTooLong));
+        }
+
+        #[ink::test]
+        fn extending_a_commit_reveal_poll_moves_the_reveal_window() {
+            let mut contract = new_contract();
+            create(&mut contract, "poll", 2, PollSettings { reveal_end_timestamp: END + HOUR, ..PollSettings::default() }).unwrap();
+            fund(accounts().bob, 100);
+            set_now(START + 1);
+            set_caller(accounts().bob);
+            contract.commit_vote("poll".into(), commitment(1, [7; 32])).unwrap();
+
+            set_caller(accounts().alice);
+            contract.extend_poll("poll".into(), END + 2 * HOUR).unwrap();
+            assert_eq!(contract.get_poll_info("poll".into()).map(|poll_info| poll_info.settings.reveal_end_timestamp), Ok(END + 3 * HOUR));
+
+            set_caller(accounts().bob);
+            set_now(END + 2 * HOUR);
+            assert_eq!(contract.reveal_vote("poll".into(), 1, [7; 32], 10), Err(Error::PollIsNotFinished));
+            set_now(END + 2 * HOUR + 1);
+            assert_eq!(contract.reveal_vote("poll".into(), 1, [7; 32], 10), Ok(()));
+            assert_eq!(contract.get_option_votes("poll".into(), 1), Ok(10));
+        }
+
+        #[ink::test]
+        fn failed_admin_actions_clear_the_executing_flag() {
+            let mut contract = new_contract();
+            contract.set_admins(vec![accounts().alice, accounts().bob], 2).unwrap();
+
+            let proposal_id = contract.propose_admin_action(AdminAction::SetFee(FEE_DENOMINATOR + 1)).unwrap();
+            set_caller(accounts().bob);
+            assert_eq!(contract.confirm_admin_action(proposal_id), Err(Error::InvalidFee));
+            assert!(!contract.executing);
+            assert_eq!(
+                contract.get_admin_proposal(proposal_id).map(|proposal| proposal.confirmations),
+                Some(vec![accounts().alice]),
+            );
+
+            set_caller(accounts().eve);
+            assert_eq!(contract.set_paused(true), Err(Error::NotAuthorized));
+            assert_eq!(contract.set_fee(0), Err(Error::NotAuthorized));
+        }
+
+        #[ink::test]
+        fn auto_id_polls_can_be_created_by_proposal() {
+            let mut contract = new_contract();
+            contract.set_admins(vec![accounts().alice, accounts().bob], 2).unwrap();
+            assert_eq!(
+                contract.create_poll_auto(2, START, END, PollSettings::default(), labels(2), String::new(), String::new()),
+                Err(Error::InsufficientConfirmations),
+            );
+
+            let proposal_id = contract.propose_admin_action(AdminAction::CreatePollAuto {
+                number_of_options: 2,
+                poll_start_timestamp: START,
+                poll_end_timestamp: END,
+                settings: PollSettings::default(),
+                option_labels: labels(2),
+                title: String::from("Title"),
+                description: String::from("Description"),
+            }).unwrap();
+            set_caller(accounts().bob);
+            contract.confirm_admin_action(proposal_id).unwrap();
+
+            let poll_ids = contract.get_poll_ids(0, 10);
+            assert_eq!(poll_ids.len(), 1);
+            assert_eq!(contract.get_poll_creator(poll_ids[0].clone()), Ok(contract_id()));
+        }
+
+        #[ink::test]
+        fn votes_past_the_cap_are_rejected() {
+            let mut contract = new_contract();
+            create(&mut contract, "poll", 2, PollSettings { max_total_votes: 30, ..PollSettings::default() }).unwrap();
+            create(&mut contract, "weighted", 2, PollSettings { voting_mode: VotingMode::MultiChoice, max_total_votes: 30, ..PollSettings::default() }).unwrap();
+            set_now(START + 1);
+            fund(accounts().bob, 2_000);
+            set_caller(accounts().bob);
+
+            assert_eq!(contract.vote("poll".into(), 1, 900), Err(Error::PollVoteCapReached));
+            assert_eq!(contract.vote("poll".into(), 1, 31), Err(Error::PollVoteCapReached));
+            contract.vote("poll".into(), 1, 25).unwrap();
+            assert_eq!(contract.vote("poll".into(), 1, 6), Err(Error::PollVoteCapReached));
+            contract.vote("poll".into(), 1, 5).unwrap();
+            assert_eq!(contract.get_option_votes("poll".into(), 1), Ok(30));
+
+            assert_eq!(contract.vote_weighted("weighted".into(), vec![(1, 900)]), Err(Error::PollVoteCapReached));
+            assert_eq!(contract.vote_weighted("weighted".into(), vec![(1, 20), (2, 11)]), Err(Error::PollVoteCapReached));
+            contract.vote_weighted("weighted".into(), vec![(1, 20), (2, 10)]).unwrap();
+            assert_eq!(balance(accounts().bob), 2_000 - 30 - 30);
+        }
+
+        #[ink::test]
+        fn weighted_votes_surface_the_status_change() {
+            let mut contract = new_contract();
+            create(&mut contract, "poll", 2, PollSettings { voting_mode: VotingMode::MultiChoice, ..PollSettings::default() }).unwrap();
+            fund(accounts().bob, 100);
+            set_now(START + 1);
+            set_caller(accounts().bob);
+            contract.vote_weighted("poll".into(), vec![(1, 10), (2, 5)]).unwrap();
+
+            let changes: Vec<_> = events()
+                .into_iter()
+                .filter_map(|event| match event {
+                    Event::PollStateChanged(changed) => Some((changed.old_status, changed.new_status)),
+                    _ => None,
+                })
+                .collect();
+            assert_eq!(changes, vec![(PollStatus::NotStarted, PollStatus::Active)]);
+            assert_eq!(contract.poll_statuses.get("poll"), Some(PollStatus::Active));
+        }
+
+        #[ink::test]
+        fn late_activation_keeps_the_minimum_duration() {
+            let mut contract = new_contract();
+            create(&mut contract, "poll", 2, PollSettings { draft: true, ..PollSettings::default() }).unwrap();
+
+            set_now(END - 1);
+            assert_eq!(contract.activate_poll("poll".into()), Err(Error::PollDurationTooShort));
+            set_now(END - DEFAULT_MIN_POLL_DURATION + 1);
+            assert_eq!(contract.activate_poll("poll".into()), Err(Error::PollDurationTooShort));
+
+            set_now(END - DEFAULT_MIN_POLL_DURATION);
+            assert_eq!(contract.activate_poll("poll".into()), Ok(()));
+            assert_eq!(
+                contract.get_poll_info("poll".into()).map(|poll_info| poll_info.poll_start_timestamp),
+                Ok(END - DEFAULT_MIN_POLL_DURATION),
+            );
+        }
+
+        #[ink::test]
+        fn failed_penalty_refunds_keep_the_whole_escrow() {
+            let mut contract = active_poll(PollSettings::default());
+            contract.set_treasury(accounts().eve).unwrap();
+            contract.set_cancel_penalty(1_000).unwrap();
+            fund(accounts().bob, 100);
+            set_caller(accounts().bob);
+            contract.vote("poll".into(), 1, 100).unwrap();
+
+            set_caller(accounts().alice);
+            contract.cancel_poll("poll".into()).unwrap();
+
+            set_caller(accounts().bob);
+            MOCK.with(|mock| mock.borrow_mut().failures.push_back(PSP22Error::InsufficientBalance));
+            assert_eq!(contract.withdrawn("poll".into()), Err(Error::TokenTransferFailed(PSP22Error::InsufficientBalance)));
+            assert_eq!((balance(accounts().bob), balance(accounts().eve), balance(contract_id())), (0, 0, 100));
+            assert_eq!(contract.get_total_locked(token()), 100);
+
+            assert_eq!(contract.withdrawn("poll".into()), Ok(()));
+            assert_eq!((balance(accounts().bob), balance(accounts().eve), balance(contract_id())), (90, 10, 0));
+            assert_eq!(contract.get_total_locked(token()), 0);
+        }
+
+        #[ink::test]
+        fn one_per_account_polls_reject_vote_bounds_above_one() {
+            let mut contract = new_contract();
+            let settings = PollSettings { voting_mode: VotingMode::OnePerAccount, ..PollSettings::default() };
+
+            assert_eq!(create(&mut contract, "min", 2, PollSettings { min_votes: 2, ..settings.clone() }), Err(Error::InvalidNumberOfVotes));
+            assert_eq!(create(&mut contract, "step", 2, PollSettings { vote_step: 2, ..settings.clone() }), Err(Error::InvalidVoteStep));
+            assert_eq!(create(&mut contract, "poll", 2, PollSettings { vote_step: 1, ..settings }), Ok(()));
+
+            set_now(START + 1);
+            set_caller(accounts().bob);
+            assert_eq!(contract.vote("poll".into(), 1, 5).map(|voting_info| voting_info.number_of_votes), Ok(1));
+        }
+    }
+
+    #[cfg(all(test, feature = "e2e-tests"))]
+    mod e2e_tests {
+        use super::*;
+        use ink_e2e::build_message;
+
+        type E2EResult<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+        #[ink_e2e::test]
+        async fn set_code_hash_keeps_storage(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            let token = AccountId::from([0x70; 32]);
+            let contract_id = client
+                .instantiate("ceres_governance", &ink_e2e::alice(), CeresGovernanceRef::new(token), 0, None)
+                .await
+                .expect("instantiate failed")
+                .account_id;
+
+            // The code is already stored, so the upload hands back the hash of the running code
+            let code_hash = client
+                .upload("ceres_governance", &ink_e2e::alice(), None)
+                .await
+                .expect("upload failed")
+                .code_hash;
+
+            let set_code_hash = build_message::<CeresGovernanceRef>(contract_id.clone())
+                .call(|contract| contract.set_code_hash(code_hash));
+            let result = client
+                .call(&ink_e2e::alice(), set_code_hash, 0, None)
+                .await
+                .expect("set_code_hash failed");
+            assert_eq!(result.return_value(), Ok(()));
+
+            let get_admins = build_message::<CeresGovernanceRef>(contract_id.clone())
+                .call(|contract| contract.get_admins());
+            let admins = client.call_dry_run(&ink_e2e::alice(), &get_admins, 0, None).await.return_value();
+            assert_eq!(admins, vec![ink_e2e::account_id(ink_e2e::AccountKeyring::Alice)]);
+
+            let get_poll_count = build_message::<CeresGovernanceRef>(contract_id)
+                .call(|contract| contract.get_poll_count());
+            let poll_count = client.call_dry_run(&ink_e2e::alice(), &get_poll_count, 0, None).await.return_value();
+            assert_eq!(poll_count, 0);
+
+            Ok(())
+        }
     }
 }